@@ -6,11 +6,27 @@ use aws_sdk_sesv2::{
 const SENDER_EMAIL_ADDRESS: &str = "awstest@chriswindsor.dev";
 
 pub async fn send_password_reset_email(to: &String, content: &String) {
+    send_email(to, "Password Reset Inquiry", content).await;
+}
+
+pub async fn send_email_verification_email(to: &String, content: &String) {
+    send_email(to, "Verify Your Email", content).await;
+}
+
+pub async fn send_restock_notification_email(to: &String, content: &String) {
+    send_email(to, "Back In Stock", content).await;
+}
+
+pub async fn send_low_stock_alert_email(to: &String, content: &String) {
+    send_email(to, "Low Stock Alert", content).await;
+}
+
+async fn send_email(to: &String, subject: &str, content: &String) {
     let config = aws_config::load_from_env().await;
     let client = Client::new(&config);
     let dest = Destination::builder().to_addresses(to).build();
     let subject_content = Content::builder()
-        .data("Password Reset Inquiry")
+        .data(subject)
         .charset("UTF-8")
         .build()
         .expect("Build subject");
@@ -9,7 +9,7 @@ use axum::{
 use axum_extra::extract::cookie::CookieJar;
 use entity::{prelude::*, *};
 use jsonwebtoken::{decode, DecodingKey, Validation};
-use rust_ecom_service_core::{sea_orm, AppState};
+use rust_ecom_service_core::{sea_orm, AppState, Query};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::Serialize;
 use std::sync::Arc;
@@ -79,6 +79,27 @@ pub async fn auth<B>(
         return Err((StatusCode::UNAUTHORIZED, Json(json_error)));
     }
 
+    let session = Query::find_active_session(&data.db, claims.sid)
+        .await
+        .map_err(|e| {
+            println!("Unable to locate session for authentication process: {}", e);
+            let json_error = ErrorResponse {
+                status: "Authentication Error",
+                message: "Error encountered while retrieving session from database".to_string(),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_error))
+        })?;
+
+    let session = session
+        .filter(|session| session.account_id == claims.sub)
+        .ok_or_else(|| {
+            let json_error = ErrorResponse {
+                status: "Authentication Error",
+                message: "The session of the provided token has ended".to_string(),
+            };
+            (StatusCode::UNAUTHORIZED, Json(json_error))
+        })?;
+
     let user = Account::find()
         .filter(account::Column::Id.eq(claims.sub))
         .one(&data.db)
@@ -100,6 +121,22 @@ pub async fn auth<B>(
         (StatusCode::UNAUTHORIZED, Json(json_error))
     })?;
 
+    let permissions = Query::find_account_permissions(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            println!(
+                "Unable to load permissions for authentication process: {}",
+                e
+            );
+            let json_error = ErrorResponse {
+                status: "Authentication Error",
+                message: "Error encountered while retrieving permissions from database".to_string(),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_error))
+        })?;
+
     req.extensions_mut().insert(user);
+    req.extensions_mut().insert(session);
+    req.extensions_mut().insert(permissions);
     Ok(next.run(req).await)
 }
@@ -7,26 +7,30 @@ mod response;
 mod route;
 mod routes;
 mod storage;
+mod tasks;
 
-use axum::Server;
+use axum::{routing::get_service, Server};
 use dotenvy::dotenv;
 use http::{
     header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
-    HeaderValue, Method,
+    HeaderValue, Method, StatusCode,
 };
 use route::{
-    create_auth_router, create_content_router, create_order_router, create_product_router,
+    create_account_router, create_auth_router, create_content_router, create_image_router,
+    create_inventory_router, create_order_router, create_product_router, create_revision_router,
+    create_role_router, create_variant_router,
 };
 use rust_ecom_service_core::{
     payment_processing::manager::get_payment_processor,
     sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr},
-    AppState, Config,
+    AppState, Config, StorageConfig,
 };
 use rust_ecom_service_plugins::load_plugin_routers;
-use std::{collections::HashMap, env, net::SocketAddr, sync::Arc, time::Duration};
-use tokio::sync::Mutex;
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
+use storage::{get_storage_backend, IMAGE_CONTENT_TYPE, LOCAL_STORAGE_ROUTE};
+use tasks::spawn_background_tasks;
 use tower::ServiceBuilder;
-use tower_http::{add_extension::AddExtensionLayer, cors::CorsLayer};
+use tower_http::{cors::CorsLayer, services::ServeDir, set_header::SetResponseHeaderLayer};
 
 #[tokio::main]
 async fn start() -> anyhow::Result<()> {
@@ -57,30 +61,52 @@ async fn start() -> anyhow::Result<()> {
         env: config.clone(),
         message_channel: Default::default(),
         payment_processor: get_payment_processor(),
+        storage: get_storage_backend(&config.storage).await,
     });
 
+    spawn_background_tasks(&app_state);
+
     let plugin_routers = load_plugin_routers(&app_state);
 
     let mut app = create_auth_router(&app_state)
         .merge(create_product_router(&app_state))
         .merge(create_order_router(&app_state))
+        .merge(create_inventory_router(&app_state))
+        .merge(create_revision_router(&app_state))
+        .merge(create_variant_router(&app_state))
+        .merge(create_image_router(&app_state))
+        .merge(create_role_router(&app_state))
+        .merge(create_account_router(&app_state))
         .merge(create_content_router(&app_state));
 
+    if let StorageConfig::Local { path, .. } = &config.storage {
+        // Keys carry no extension, so the content type can't be guessed from the path
+        let files = ServiceBuilder::new()
+            .layer(SetResponseHeaderLayer::overriding(
+                CONTENT_TYPE,
+                HeaderValue::from_static(IMAGE_CONTENT_TYPE),
+            ))
+            .service(ServeDir::new(path));
+        let uploads = get_service(files).handle_error(|e: std::io::Error| async move {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Storage error: {}", e),
+            )
+        });
+        app = app.nest_service(LOCAL_STORAGE_ROUTE, uploads);
+    }
+
     // TODO: improve builder of app
     for router in plugin_routers {
         app = app.clone().merge(router);
     }
 
-    app = app
-        .layer(
-            ServiceBuilder::new()
-                .layer(AddExtensionLayer::new(SharedState::default()))
-                .into_inner(),
-        )
-        .layer(cors);
+    app = app.layer(cors);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], config.api_port));
-    Server::bind(&addr).serve(app.into_make_service()).await?;
+    Server::bind(&addr)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await?;
 
     Ok(())
 }
@@ -99,48 +125,6 @@ async fn establish_db_conection(config: &Config) -> Result<DatabaseConnection, D
     Ok(db)
 }
 
-type SharedState = Arc<Mutex<State>>;
-
-#[derive(Default)]
-pub struct State {
-    reset_tokens: ResetTokenDB,
-    reset_requests: ResetRequestDB,
-}
-
-#[derive(Default)]
-pub struct ResetTokenDB {
-    tokens: HashMap<String, i32>,
-}
-
-impl ResetTokenDB {
-    fn add_token(&mut self, token: String, user_id: i32) {
-        self.tokens.insert(token, user_id);
-    }
-
-    fn get_token(&self, token: &String) -> Option<i32> {
-        self.tokens.get(token).copied()
-    }
-
-    fn remove_token(&mut self, token: &String) {
-        self.tokens.remove(&token.to_owned());
-    }
-}
-
-#[derive(Default)]
-pub struct ResetRequestDB {
-    requests: HashMap<String, usize>,
-}
-
-impl ResetRequestDB {
-    fn add_request(&mut self, email: String, time: usize) {
-        self.requests.insert(email, time);
-    }
-
-    fn get_request(&self, email: &String) -> Option<&usize> {
-        self.requests.get(email)
-    }
-}
-
 pub fn main() {
     let result = start();
 
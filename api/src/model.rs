@@ -3,10 +3,11 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: i32,
+    /// The session the token was issued for
+    pub sid: i32,
     pub iat: usize,
     pub exp: usize,
     pub name: String,
-    pub role: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,3 +33,14 @@ pub struct ResetPasswordSchema {
     pub token: String,
     pub password: String,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct RefreshTokenSchema {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailSchema {
+    pub token: String,
+}
@@ -1,36 +1,52 @@
-use axum::{middleware::Next, response::IntoResponse, Extension, Json};
+use axum::{extract::State, middleware::Next, response::IntoResponse, Extension, Json};
 use entity::account;
 use http::{Request, StatusCode};
+use rust_ecom_service_core::{
+    permission::{AccountPermissions, Permission},
+    AppState,
+};
+use std::sync::Arc;
 
 use crate::jwt::ErrorResponse;
 
-// TODO: Would be great to figure out how to chain the 'route_layer's and use this
-pub async fn admin_auth<B>(
-    Extension(user): Extension<account::Model>,
-    mut req: Request<B>,
+/// Turns away accounts lacking the permission the layer was built with, for use as
+/// `middleware::from_fn_with_state(Permission::CatalogManage, require_permission)`
+/// layered inside `auth`
+pub async fn require_permission<B>(
+    State(permission): State<Permission>,
+    Extension(permissions): Extension<AccountPermissions>,
+    req: Request<B>,
     next: Next<B>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    if user.role != "admin" {
+    if !permissions.has(permission) {
         let json_error = ErrorResponse {
             status: "Authentication Error",
-            message: "Insufficient priveleges to complete the request".to_string(),
+            message: format!(
+                "Insufficient privileges to complete the request, requires {}",
+                permission.as_str()
+            ),
         };
-        return Err((StatusCode::UNAUTHORIZED, Json(json_error)));
+        return Err((StatusCode::FORBIDDEN, Json(json_error)));
     }
 
-    req.extensions_mut().insert(user);
     Ok(next.run(req).await)
 }
 
-// TODO: ditch this in favor of the methodology
-pub fn check_admin(user: &account::Model) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
-    if user.role != "admin" {
-        let error_response = serde_json::json!({
-            "status": "fail",
-            "message": "Insufficient privileges to complete the request",
-        });
-        return Err((StatusCode::CONFLICT, Json(error_response)));
-    } else {
-        Ok(())
+/// Turns away accounts that have yet to verify their email, when `REQUIRE_VERIFIED_EMAIL`
+/// is on. Layered inside `auth`.
+pub async fn verified_email<B>(
+    State(data): State<Arc<AppState>>,
+    Extension(user): Extension<account::Model>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if data.env.require_verified_email && !user.email_verified {
+        let json_error = ErrorResponse {
+            status: "Authentication Error",
+            message: "Email address must be verified to complete the request".to_string(),
+        };
+        return Err((StatusCode::FORBIDDEN, Json(json_error)));
     }
+
+    Ok(next.run(req).await)
 }
@@ -1,21 +1,87 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct NewProduct {
-    pub name: String,
     pub short_url: String,
-    pub description: String,
-    pub price: f32,
+    pub status: Option<String>,
+    pub publish_date: Option<DateTime<Utc>>,
+    pub unpublish_date: Option<DateTime<Utc>>,
     pub categories: Vec<u32>,
-    pub attributes: Vec<u32>,
+    /// Defaults to the first of `categories`
+    pub primary_category_id: Option<i32>,
+    /// Initial stock, only received when the product is first created
+    #[serde(default)]
     pub stock: i32,
-    pub image_id: String,
+    pub allow_back_order: Option<bool>,
+    pub allow_restock_notifications: Option<bool>,
+    #[serde(flatten)]
+    pub revision: NewProductRevision,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NewProductRevision {
+    pub name: String,
+    pub description: String,
+    pub price: f32,
+    pub upc: Option<String>,
+    pub real_weight: Option<i32>,
+    pub ship_weight: Option<i32>,
+    pub attributes: Vec<NewProductAttribute>,
+    #[serde(default)]
+    pub images: Vec<NewProductImage>,
+}
+
+/// An uploaded image given by id alone, or along with the alt text to show for it
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NewProductImage {
+    Id(String),
+    #[serde(rename_all(deserialize = "camelCase"))]
+    Described {
+        id: String,
+        alt_text: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewProductImages {
+    pub images: Vec<NewProductImage>,
+    /// Where to insert the images in the gallery, appended when unset
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProductImageOrder {
+    pub images: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct UpdatedProductImage {
+    pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NewRevisionSchedule {
+    pub publish_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NewProductAttribute {
+    pub attribute_id: i32,
+    pub content: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct NewAttributeOption {
+    /// Existing option to keep when editing an attribute
+    pub id: Option<i32>,
     pub label: String,
     pub content: String,
 }
@@ -32,6 +98,7 @@ pub struct NewAttribute {
 pub struct NewCategory {
     pub label: String,
     pub parent_id: Option<i32>,
+    pub position: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -63,6 +130,7 @@ pub struct PaymentDetails {
 pub struct OrderItem {
     pub id: Arc<str>,
     pub qty: i32,
+    pub variant_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -72,3 +140,74 @@ pub struct NewOrder {
     pub payment_details: PaymentDetails,
     pub order_items: Vec<OrderItem>,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NewStockReceipt {
+    pub amount: i32,
+    pub location_id: Option<i32>,
+    pub variant_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NewStockTransfer {
+    pub product_id: i32,
+    pub variant_id: Option<i32>,
+    pub from_location_id: i32,
+    pub to_location_id: i32,
+    pub amount: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdatedProductVariant {
+    pub sku: String,
+    pub price: Option<f32>,
+    pub active: Option<bool>,
+    #[serde(default)]
+    pub images: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewLocation {
+    pub label: String,
+    pub priority: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdatedLocation {
+    pub label: Option<String>,
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewRestockSubscription {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct UpdatedLowStockThreshold {
+    pub low_stock_threshold: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewRole {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdatedRole {
+    pub name: Option<String>,
+    /// Kept when unset, cleared when empty
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct AccountRoles {
+    pub role_ids: Vec<i32>,
+}
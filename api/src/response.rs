@@ -1,27 +1,158 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
 pub struct FilteredUser {
     pub id: String,
     pub name: String,
     pub email: String,
     pub role: String,
+    pub email_verified: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredSession {
+    pub id: i32,
+    pub user_agent: Option<String>,
+    pub creation_date: NaiveDateTime,
+    pub last_used_date: NaiveDateTime,
+    pub expiry_date: NaiveDateTime,
+    pub current: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct FilteredSimpleProduct {
+    pub id: i32,
     pub short_url: String,
-    pub img: String,
+    pub name: String,
+    pub price: Decimal,
+    pub img: Option<String>,
+    pub category_id: Option<i32>,
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct FilteredProduct {
+    pub id: i32,
     pub short_url: String,
-    pub img: String,
+    pub status: String,
+    pub publish_date: Option<NaiveDateTime>,
+    pub unpublish_date: Option<NaiveDateTime>,
+    pub name: String,
+    pub description: String,
+    pub price: Decimal,
+    pub upc: Option<String>,
+    pub real_weight: Option<i32>,
+    pub ship_weight: Option<i32>,
+    pub img: Option<String>,
+    pub images: Vec<String>,
+    pub image_details: Vec<FilteredProductImage>,
     pub categories: Vec<FilteredCategory>,
+    pub primary_category_id: Option<i32>,
     pub attributes: Vec<FilteredProductAttribute>,
+    pub variants: Vec<FilteredProductVariant>,
+    pub availability: FilteredAvailability,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredProductImage {
+    pub id: String,
+    pub url: String,
+    pub alt_text: Option<String>,
+    pub position: i32,
+    pub variants: Vec<FilteredImageVariant>,
+    pub srcset: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredMedia {
+    pub id: String,
+    pub url: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size: i64,
+    pub uploaded_by: Option<FilteredMediaUploader>,
+    pub upload_date: NaiveDateTime,
+    pub usage: u64,
+    pub variants: Vec<FilteredImageVariant>,
+    pub srcset: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FilteredMediaUploader {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredStoredObject {
+    pub key: String,
+    pub url: String,
+    pub size: u64,
+    pub last_modified: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredImageVariant {
+    pub name: String,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredProductVariant {
+    pub id: i32,
+    pub sku: String,
+    pub price: Decimal,
+    pub active: bool,
+    pub img: Option<String>,
+    pub images: Vec<String>,
+    pub options: Vec<FilteredVariantOption>,
+    pub available: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredVariantOption {
+    pub attribute_id: i32,
+    pub attribute: String,
+    pub option_id: i32,
+    pub label: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredSearchResult {
+    pub id: i32,
+    pub short_url: String,
+    pub name: String,
+    pub price: Decimal,
+    pub img: Option<String>,
+    pub snippet: String,
+    pub rank: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredAvailability {
+    pub in_stock: bool,
+    pub available: i64,
+    pub allow_back_order: bool,
+    pub allow_restock_notifications: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -31,6 +162,7 @@ pub struct FilteredAttributeOption {
     pub label: String,
     pub content: String,
     pub attribute_id: i32,
+    pub position: i32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -45,7 +177,59 @@ pub struct FilteredAttribute {
 pub struct FilteredCategory {
     pub id: i32,
     pub label: String,
-    pub parent_id: i32,
+    pub parent_id: Option<i32>,
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredCategoryNode {
+    pub id: i32,
+    pub label: String,
+    pub parent_id: Option<i32>,
+    pub position: i32,
+    pub children: Vec<FilteredCategoryNode>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredProductFacets {
+    pub categories: Vec<FilteredCategoryFacet>,
+    pub attributes: Vec<FilteredAttributeFacet>,
+    pub prices: Vec<FilteredPriceFacet>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredCategoryFacet {
+    pub id: i32,
+    pub label: String,
+    pub parent_id: Option<i32>,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FilteredAttributeFacet {
+    pub id: i32,
+    pub label: String,
+    pub kind: String,
+    pub options: Vec<FilteredOptionFacet>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FilteredOptionFacet {
+    pub id: i32,
+    pub label: String,
+    pub content: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredPriceFacet {
+    pub min_price: Decimal,
+    pub max_price: Option<Decimal>,
+    pub count: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -54,4 +238,95 @@ pub struct FilteredProductAttribute {
     pub kind: String,
     pub label: String,
     pub content: String,
+    pub options: Vec<FilteredAttributeOption>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FilteredLocation {
+    pub id: i32,
+    pub label: String,
+    pub priority: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredProductRevision {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub name: String,
+    pub price: Decimal,
+    pub status: String,
+    pub creation_date: NaiveDateTime,
+    pub scheduled_date: Option<NaiveDateTime>,
+    pub live: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredProductEvent {
+    pub id: i32,
+    pub revision_id: Option<i32>,
+    pub kind: String,
+    pub creation_date: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FilteredSimpleRole {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FilteredRole {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredLoginLockout {
+    pub id: i32,
+    pub kind: String,
+    pub key: String,
+    pub failure_count: i32,
+    pub last_failure_date: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredLoginEvent {
+    pub id: i32,
+    pub kind: String,
+    pub email: Option<String>,
+    pub ip_address: Option<String>,
+    pub account_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub creation_date: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredOrder {
+    pub id: i32,
+    pub status: String,
+    pub email: String,
+    pub tax_amount: Decimal,
+    pub shipping_amount: Decimal,
+    pub total_amount: Decimal,
+    pub creation_date: NaiveDateTime,
+    pub items: Vec<FilteredOrderItem>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FilteredOrderItem {
+    pub id: i32,
+    pub product_id: i32,
+    pub variant_id: Option<i32>,
+    pub price: Decimal,
+    pub qty: i32,
 }
@@ -1,25 +1,51 @@
 use crate::{
     jwt::auth,
+    priveleges::{require_permission, verified_email},
     routes::{
+        account::{list_login_events, list_login_lockouts, unlock_account},
         auth::{
             change_password_handler, get_me_handler, inquire_password_reset_handler,
-            login_user_handler, logout_handler, register_user_handler,
+            list_sessions_handler, login_user_handler, logout_handler, refresh_token_handler,
+            register_user_handler, resend_verification_handler, revoke_all_sessions_handler,
+            revoke_session_handler, verify_email_handler,
         },
         content::content_page,
-        orders::{list_orders, live_order_events, process_order},
+        image::{
+            attach_product_images, delete_orphaned_images, delete_uploaded_image,
+            detach_product_image, list_product_images, orphaned_images_report,
+            reorder_product_images, update_product_image,
+        },
+        inventory::{
+            create_location, inventory_report, list_locations, product_stock, receive_stock,
+            subscribe_to_restock, transfer_stock, update_location, update_low_stock_threshold,
+        },
+        orders::{list_account_orders, list_orders, live_order_events, process_order},
         product::{
-            all_products, create_attribute, create_category, create_product, list_attributes,
-            list_categories, list_product, list_uploaded_images, retrieve_attribute,
-            retrieve_category, update_attribute, update_category, upload_product_image,
+            all_products, create_attribute, create_category, create_product, delete_attribute,
+            delete_category, delete_product, list_attributes, list_categories, list_product,
+            list_uploaded_images, product_facets, retrieve_attribute, retrieve_category,
+            search_products, update_attribute, update_category, update_product,
+            upload_product_image,
+        },
+        revision::{
+            create_draft, diff_revisions, list_product_events, list_revisions, publish_draft,
+            rollback_revision, schedule_draft,
+        },
+        role::{
+            create_role, delete_role, list_account_roles, list_permissions, list_roles,
+            update_account_roles, update_role,
         },
+        variant::{generate_variants, list_variants, update_variant},
     },
+    storage::MAX_UPLOAD_REQUEST_BYTES,
 };
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
-use rust_ecom_service_core::AppState;
+use rust_ecom_service_core::{permission::Permission, AppState};
 use std::sync::Arc;
 
 pub fn create_auth_router(app_state: &Arc<AppState>) -> Router {
@@ -31,11 +57,29 @@ pub fn create_auth_router(app_state: &Arc<AppState>) -> Router {
             post(inquire_password_reset_handler),
         )
         .route("/api/auth/change_password", post(change_password_handler))
+        .route("/api/auth/refresh", post(refresh_token_handler))
+        .route("/api/auth/verify_email", post(verify_email_handler))
+        .route(
+            "/api/auth/resend_verification",
+            post(resend_verification_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
         .route(
             "/api/auth/logout",
             get(logout_handler)
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
+        .route(
+            "/api/auth/sessions",
+            get(list_sessions_handler)
+                .delete(revoke_all_sessions_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/auth/sessions/:session_id",
+            delete(revoke_session_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
         .route(
             "/api/users/me",
             get(get_me_handler)
@@ -47,44 +91,87 @@ pub fn create_auth_router(app_state: &Arc<AppState>) -> Router {
 pub fn create_product_router(app_state: &Arc<AppState>) -> Router {
     Router::new()
         .route("/api/products", get(all_products))
+        .route("/api/products/search", get(search_products))
+        .route("/api/products/facets", get(product_facets))
         .route("/api/product/:product_id", get(list_product))
+        .route(
+            "/api/product/:product_id",
+            patch(update_product)
+                .delete(delete_product)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
         .route(
             "/api/product",
             post(create_product)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .route(
             "/api/upload_file",
             post(upload_product_image)
-                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                .layer(DefaultBodyLimit::max(MAX_UPLOAD_REQUEST_BYTES)),
         )
         .route(
             "/api/list_files",
             get(list_uploaded_images)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .route("/api/product/attributes", get(list_attributes))
         .route(
             "/api/product/attribute",
             post(create_attribute)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .route(
             "/api/product/attribute/:attribute_id",
             get(retrieve_attribute)
                 .patch(update_attribute)
+                .delete(delete_attribute)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .route("/api/product/categories", get(list_categories))
         .route(
             "/api/product/category",
             post(create_category)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
+        .route("/api/product/category/:category_id", get(retrieve_category))
         .route(
-            "/api/product/catgeory/:category_id",
-            get(retrieve_category)
-                .patch(update_category)
+            "/api/product/category/:category_id",
+            patch(update_category)
+                .delete(delete_category)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .with_state(app_state.to_owned())
@@ -95,16 +182,267 @@ pub fn create_order_router(app_state: &Arc<AppState>) -> Router {
         .route("/api/process_order", post(process_order))
         .route(
             "/api/list_orders",
-            get(list_orders).route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+            get(list_orders)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::OrdersView,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/users/me/orders",
+            get(list_account_orders)
+                .route_layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    verified_email,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .route(
             "/api/live_order_events",
             get(live_order_events)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::OrdersView,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state.to_owned())
+}
+
+pub fn create_inventory_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/api/product/:product_id/stock",
+            get(product_stock)
+                .post(receive_stock)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/low_stock_threshold",
+            patch(update_low_stock_threshold)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/inventory/transfer",
+            post(transfer_stock)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/inventory/locations",
+            get(list_locations)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/inventory/location",
+            post(create_location)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/inventory/location/:location_id",
+            patch(update_location)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/inventory/report",
+            get(inventory_report)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::InventoryManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/restock_notification",
+            post(subscribe_to_restock),
+        )
+        .with_state(app_state.to_owned())
+}
+
+pub fn create_revision_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/api/product/:product_id/revisions",
+            get(list_revisions)
+                .post(create_draft)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/revisions/diff",
+            get(diff_revisions)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/revision/:revision_id/publish",
+            post(publish_draft)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/revision/:revision_id/schedule",
+            post(schedule_draft)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/events",
+            get(list_product_events)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/revision/:revision_id/rollback",
+            post(rollback_revision)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state.to_owned())
+}
+
+pub fn create_variant_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route("/api/product/:product_id/variants", get(list_variants))
+        .route(
+            "/api/product/:product_id/variants/generate",
+            post(generate_variants)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/variant/:variant_id",
+            patch(update_variant)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
         .with_state(app_state.to_owned())
 }
 
+pub fn create_image_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/api/product/:product_id/images",
+            get(list_product_images)
+                .post(attach_product_images)
+                .patch(reorder_product_images)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/product/:product_id/image/:image_id",
+            patch(update_product_image)
+                .delete(detach_product_image)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/image/:image_id",
+            delete(delete_uploaded_image)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/images/orphaned",
+            get(orphaned_images_report)
+                .delete(delete_orphaned_images)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::CatalogManage,
+                    require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state.to_owned())
+}
+
+pub fn create_role_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route("/api/permissions", get(list_permissions))
+        .route("/api/roles", get(list_roles))
+        .route("/api/role", post(create_role))
+        .route("/api/role/:role_id", patch(update_role).delete(delete_role))
+        .route(
+            "/api/account/:account_id/roles",
+            get(list_account_roles).put(update_account_roles),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Permission::RolesManage,
+            require_permission,
+        ))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        .with_state(app_state.to_owned())
+}
+
+pub fn create_account_router(app_state: &Arc<AppState>) -> Router {
+    Router::new()
+        .route("/api/login_lockouts", get(list_login_lockouts))
+        .route("/api/login_events", get(list_login_events))
+        .route("/api/account/:account_id/unlock", post(unlock_account))
+        .route_layer(middleware::from_fn_with_state(
+            Permission::AccountsManage,
+            require_permission,
+        ))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        .with_state(app_state.to_owned())
+}
+
 pub fn create_content_router(app_state: &Arc<AppState>) -> Router {
     Router::new()
         .route("/api/content", get(content_page))
@@ -0,0 +1,160 @@
+use crate::response::{FilteredLoginEvent, FilteredLoginLockout};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use entity::{prelude::*, *};
+use rust_ecom_service_core::{
+    auth::{LOGIN_EVENT_UNLOCK, LOGIN_THROTTLE_ACCOUNT},
+    sea_orm::{ActiveValue, EntityTrait},
+    AppState, Mutation, Query,
+};
+use std::sync::Arc;
+
+const LOGIN_EVENTS_PER_REQUEST: u64 = 100;
+
+fn filter_login_lockout_record(throttle: &login_throttle::Model) -> FilteredLoginLockout {
+    FilteredLoginLockout {
+        id: throttle.id,
+        kind: throttle.kind.to_owned(),
+        key: throttle.key.to_owned(),
+        failure_count: throttle.failure_count,
+        last_failure_date: throttle.last_failure_date,
+        locked_until: throttle.locked_until,
+    }
+}
+
+fn filter_login_event_record(event: &login_event::Model) -> FilteredLoginEvent {
+    FilteredLoginEvent {
+        id: event.id,
+        kind: event.kind.to_owned(),
+        email: event.email.to_owned(),
+        ip_address: event.ip_address.to_owned(),
+        account_id: event.account_id,
+        actor_id: event.actor_id,
+        creation_date: event.creation_date,
+    }
+}
+
+pub async fn find_account(
+    data: &Arc<AppState>,
+    account_id: i32,
+) -> Result<account::Model, (StatusCode, Json<serde_json::Value>)> {
+    Account::find_by_id(account_id)
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Account not found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+/// Email addresses and IP addresses locked out of logging in right now
+pub async fn list_login_lockouts(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let lockouts = Query::find_login_lockouts(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "lockouts": lockouts
+                .iter()
+                .map(filter_login_lockout_record)
+                .collect::<Vec<FilteredLoginLockout>>(),
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+/// The audit trail of lockouts and unlocks
+pub async fn list_login_events(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let events = Query::find_login_events(&data.db, LOGIN_EVENTS_PER_REQUEST)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "events": events
+                .iter()
+                .map(filter_login_event_record)
+                .collect::<Vec<FilteredLoginEvent>>(),
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+/// Lifts the lockout and backoff on an account's email address. Lockouts of the IP
+/// addresses it was attacked from stay in place.
+pub async fn unlock_account(
+    State(data): State<Arc<AppState>>,
+    Path(account_id): Path<i32>,
+    Extension(user): Extension<account::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let account = find_account(&data, account_id).await?;
+
+    Mutation::clear_login_failures(&data.db, LOGIN_THROTTLE_ACCOUNT, &account.email)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    println!(
+        "Login unlocked for account {} by account {}",
+        account.id, user.id
+    );
+    Mutation::create_login_event(
+        &data.db,
+        login_event::ActiveModel {
+            kind: ActiveValue::Set(LOGIN_EVENT_UNLOCK.to_owned()),
+            email: ActiveValue::Set(Some(account.email.to_owned())),
+            ip_address: ActiveValue::Set(None),
+            account_id: ActiveValue::Set(Some(account.id)),
+            actor_id: ActiveValue::Set(Some(user.id)),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
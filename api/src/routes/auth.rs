@@ -1,19 +1,18 @@
 use crate::{
-    email::send_password_reset_email,
+    email::{send_email_verification_email, send_password_reset_email},
     model::{
-        InquirePasswordResetSchema, LoginUserSchema, RegisterUserSchema, ResetPasswordSchema,
-        TokenClaims,
+        InquirePasswordResetSchema, LoginUserSchema, RefreshTokenSchema, RegisterUserSchema,
+        ResetPasswordSchema, TokenClaims, VerifyEmailSchema,
     },
-    response::FilteredUser,
-    SharedState,
+    response::{FilteredSession, FilteredUser},
 };
 use argon2::{
     password_hash::{rand_core, SaltString},
     Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
 };
 use axum::{
-    extract::State,
-    http::{header, Response, StatusCode},
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, Response, StatusCode},
     response::IntoResponse,
     Extension, Json,
 };
@@ -22,12 +21,21 @@ use entity::{prelude::*, *};
 use jsonwebtoken::{encode, EncodingKey, Header};
 use rand_core::OsRng;
 use rust_ecom_service_core::{
-    sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter},
-    AppState,
+    auth::{
+        decode_verification_token, encode_verification_token, generate_token, hash_token,
+        LoginThrottlePolicy, RefreshOutcome, ACCOUNT_LOGIN_POLICY,
+        EMAIL_VERIFICATION_RESEND_DELAY_MINUTES, IP_LOGIN_POLICY, LOGIN_EVENT_LOCKOUT,
+        LOGIN_THROTTLE_ACCOUNT, LOGIN_THROTTLE_IP, PASSWORD_RESET_REQUEST_DELAY_MINUTES,
+    },
+    permission::AccountPermissions,
+    sea_orm::{ActiveValue, ColumnTrait, EntityTrait, QueryFilter},
+    AppState, Mutation, Query,
 };
 use serde_json::json;
-use std::sync::Arc;
-use uuid::Uuid;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, OnceLock},
+};
 
 fn filter_user_record(user: &account::Model) -> FilteredUser {
     FilteredUser {
@@ -35,10 +43,205 @@ fn filter_user_record(user: &account::Model) -> FilteredUser {
         email: user.email.to_owned(),
         name: user.name.to_owned(),
         role: user.role.to_owned(),
+        email_verified: user.email_verified,
+    }
+}
+
+fn filter_session_record(
+    session: &account_session::Model,
+    current: &account_session::Model,
+) -> FilteredSession {
+    FilteredSession {
+        id: session.id,
+        user_agent: session.user_agent.to_owned(),
+        creation_date: session.creation_date,
+        last_used_date: session.last_used_date,
+        expiry_date: session.expiry_date,
+        current: session.id == current.id,
     }
 }
 
-const PASSWWORD_RESET_REQUEST_DELAY: usize = 3600;
+fn issue_access_token(data: &AppState, user: &account::Model, session_id: i32) -> String {
+    let now = chrono::Utc::now();
+    let iat = now.timestamp() as usize;
+    let exp = (now + chrono::Duration::seconds(data.env.jwt_expiry)).timestamp() as usize;
+    let claims: TokenClaims = TokenClaims {
+        sub: user.id,
+        sid: session_id,
+        exp,
+        iat,
+        name: user.name.to_owned(),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(data.env.jwt_secret.as_ref()),
+    )
+    .unwrap()
+}
+
+async fn find_permissions(
+    data: &AppState,
+    user: &account::Model,
+) -> Result<AccountPermissions, (StatusCode, Json<serde_json::Value>)> {
+    Query::find_account_permissions(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })
+}
+
+/// Checked against when the email of a login matches no account, so that it takes as long
+/// to turn away as a wrong password
+fn missing_account_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(generate_token().as_bytes(), &salt)
+            .unwrap()
+            .to_string()
+    })
+}
+
+fn client_ip_address(data: &AppState, headers: &HeaderMap, addr: SocketAddr) -> String {
+    let forwarded = headers
+        .get("x-forwarded-for")
+        .and_then(|forwarded| forwarded.to_str().ok())
+        .and_then(|forwarded| forwarded.rsplit(',').next())
+        .and_then(|forwarded| forwarded.trim().parse::<IpAddr>().ok())
+        .filter(|_| data.env.trust_forwarded_for);
+
+    forwarded.unwrap_or(addr.ip()).to_string()
+}
+
+/// The throttles a login is subject to, one for its email address and one for its IP address
+fn login_throttles<'a>(
+    email: &'a str,
+    ip_address: &'a str,
+) -> [(&'static str, &'a str, LoginThrottlePolicy); 2] {
+    [
+        (LOGIN_THROTTLE_ACCOUNT, email, ACCOUNT_LOGIN_POLICY),
+        (LOGIN_THROTTLE_IP, ip_address, IP_LOGIN_POLICY),
+    ]
+}
+
+/// Counts a login against its throttles before the password is checked, turning it away
+/// if made before a backoff or lockout is over. Emails without an account are throttled
+/// the same way so the response never tells whether one exists.
+async fn reserve_login_attempt(
+    data: &AppState,
+    email: &str,
+    ip_address: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let retry_date = Mutation::reserve_login_attempt(&data.db, &login_throttles(email, ip_address))
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if let Some(retry_date) = retry_date {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Too many failed login attempts, try again later",
+            "retryAfter": (retry_date - now).num_seconds().max(1),
+        });
+        return Err((StatusCode::TOO_MANY_REQUESTS, Json(error_response)));
+    }
+
+    Ok(())
+}
+
+async fn record_login_failure(
+    data: &AppState,
+    email: &str,
+    ip_address: &str,
+    user: Option<&account::Model>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    for (kind, key, policy) in login_throttles(email, ip_address) {
+        let throttle = Mutation::record_login_failure(&data.db, kind, key, &policy)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+        let Some(throttle) = throttle else {
+            continue;
+        };
+
+        println!(
+            "Login locked out for {} {} after {} failed attempts",
+            kind, key, throttle.failure_count
+        );
+        let by_account = kind == LOGIN_THROTTLE_ACCOUNT;
+        Mutation::create_login_event(
+            &data.db,
+            login_event::ActiveModel {
+                kind: ActiveValue::Set(LOGIN_EVENT_LOCKOUT.to_owned()),
+                email: ActiveValue::Set(Some(email.to_owned()).filter(|_| by_account)),
+                ip_address: ActiveValue::Set(Some(ip_address.to_owned())),
+                account_id: ActiveValue::Set(user.map(|user| user.id).filter(|_| by_account)),
+                actor_id: ActiveValue::Set(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+    }
+
+    Ok(())
+}
+
+async fn send_verification_email(
+    data: &AppState,
+    user: &account::Model,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let token = encode_verification_token(
+        data.env.jwt_secret.as_ref(),
+        user.id,
+        &user.email,
+        chrono::Utc::now(),
+    );
+
+    Mutation::record_verification_email(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let verify_url = format!(
+        "http://{}/auth/verifyemail?token={}",
+        &data.env.web_host, token
+    );
+    let verify_email_content = format!("<a href='{}'>Verify your email</a>", verify_url);
+    send_email_verification_email(&user.email, &verify_email_content).await;
+
+    Ok(())
+}
 
 pub async fn register_user_handler(
     State(data): State<Arc<AppState>>,
@@ -94,6 +297,8 @@ pub async fn register_user_handler(
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
 
+    send_verification_email(&data, &user).await?;
+
     let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
         "user": filter_user_record(&user)
     })});
@@ -103,10 +308,17 @@ pub async fn register_user_handler(
 
 pub async fn login_user_handler(
     State(data): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(body): Json<LoginUserSchema>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let email = body.email.to_owned().to_ascii_lowercase();
+    let ip_address = client_ip_address(&data, &headers, addr);
+
+    reserve_login_attempt(&data, &email, &ip_address).await?;
+
     let user = Account::find()
-        .filter(account::Column::Email.eq(body.email.to_owned().to_ascii_lowercase()))
+        .filter(account::Column::Email.eq(email.to_owned()))
         .one(&data.db)
         .await
         .map_err(|e| {
@@ -115,54 +327,165 @@ pub async fn login_user_handler(
                 "message": format!("Database error: {}", e),
             });
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?
-        .ok_or_else(|| {
-            let error_response = serde_json::json!({
-                "status": "fail",
-                "message": "Invalid email or password",
-            });
-            (StatusCode::BAD_REQUEST, Json(error_response))
         })?;
 
-    let is_valid = match PasswordHash::new(&user.password) {
+    let password_hash = user
+        .as_ref()
+        .map_or(missing_account_password_hash(), |user| {
+            user.password.as_str()
+        });
+    let is_valid = match PasswordHash::new(password_hash) {
         Ok(parsed_hash) => Argon2::default()
             .verify_password(body.password.as_bytes(), &parsed_hash)
             .map_or(false, |_| true),
         Err(_) => false,
     };
 
-    if !is_valid {
+    let user = match user {
+        Some(user) if is_valid => user,
+        user => {
+            record_login_failure(&data, &email, &ip_address, user.as_ref()).await?;
+
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Invalid email or password"
+            });
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+    };
+
+    Mutation::clear_login_failures(&data.db, LOGIN_THROTTLE_ACCOUNT, &email)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+    Mutation::release_login_attempt(&data.db, LOGIN_THROTTLE_IP, &ip_address)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let refresh_token = generate_token();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.to_owned());
+    let session = Mutation::create_session(
+        &data.db,
+        user.id,
+        user_agent,
+        hash_token(&refresh_token),
+        chrono::Duration::days(data.env.refresh_token_expiry_days),
+    )
+    .await
+    .map_err(|e| {
         let error_response = serde_json::json!({
             "status": "fail",
-            "message": "Invalid email or password"
+            "message": format!("Database error: {}", e),
         });
-        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
-    }
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
 
-    let now = chrono::Utc::now();
-    let iat = now.timestamp() as usize;
-    let exp = (now + chrono::Duration::seconds(data.env.jwt_expiry)).timestamp() as usize;
-    let claims: TokenClaims = TokenClaims {
-        sub: user.id,
-        exp,
-        iat,
-        name: user.name,
-        role: user.role.to_string(),
-    };
+    let token = issue_access_token(&data, &user, session.id);
+    let permissions = find_permissions(&data, &user).await?;
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(data.env.jwt_secret.as_ref()),
+    let response = Response::new(
+        json!({"status": "success", "token": token, "refreshToken": refresh_token, "permissions": permissions.names()})
+            .to_string(),
+    );
+    Ok(response)
+}
+
+/// Trades a refresh token for a new access token and refresh token
+pub async fn refresh_token_handler(
+    State(data): State<Arc<AppState>>,
+    Json(body): Json<RefreshTokenSchema>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let refresh_token = generate_token();
+    let outcome = Mutation::rotate_refresh_token(
+        &data.db,
+        &hash_token(&body.refresh_token),
+        hash_token(&refresh_token),
+        chrono::Duration::days(data.env.refresh_token_expiry_days),
     )
-    .unwrap();
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let session = match outcome {
+        RefreshOutcome::Rotated(session) => session,
+        RefreshOutcome::Reused => {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Refresh token has already been used, its session has been revoked",
+            });
+            return Err((StatusCode::UNAUTHORIZED, Json(error_response)));
+        }
+        RefreshOutcome::Invalid => {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Invalid or expired refresh token provided",
+            });
+            return Err((StatusCode::UNAUTHORIZED, Json(error_response)));
+        }
+    };
 
-    let response =
-        Response::new(json!({"status": "success", "token": token, "role": user.role}).to_string());
+    let user = Account::find_by_id(session.account_id)
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Unable to locate the user that owns the provided token",
+            });
+            (StatusCode::UNAUTHORIZED, Json(error_response))
+        })?;
+
+    let token = issue_access_token(&data, &user, session.id);
+    let permissions = find_permissions(&data, &user).await?;
+
+    let response = Response::new(
+        json!({"status": "success", "token": token, "refreshToken": refresh_token, "permissions": permissions.names()})
+            .to_string(),
+    );
     Ok(response)
 }
 
-pub async fn logout_handler() -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+pub async fn logout_handler(
+    State(data): State<Arc<AppState>>,
+    Extension(user): Extension<account::Model>,
+    Extension(session): Extension<account_session::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    Mutation::revoke_session(&data.db, user.id, session.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
     let cookie = Cookie::build("token", "")
         .path("/")
         .max_age(time::Duration::hours(-1))
@@ -179,19 +502,187 @@ pub async fn logout_handler() -> Result<impl IntoResponse, (StatusCode, Json<ser
 
 pub async fn get_me_handler(
     Extension(user): Extension<account::Model>,
+    Extension(permissions): Extension<AccountPermissions>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let json_response = serde_json::json!({
         "status":  "success",
         "data": serde_json::json!({
-            "user": filter_user_record(&user)
+            "user": filter_user_record(&user),
+            "permissions": permissions.names(),
         })
     });
 
     Ok(Json(json_response))
 }
 
+pub async fn list_sessions_handler(
+    State(data): State<Arc<AppState>>,
+    Extension(user): Extension<account::Model>,
+    Extension(current): Extension<account_session::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let sessions = Query::find_account_sessions(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "sessions": sessions
+                .iter()
+                .map(|session| filter_session_record(session, &current))
+                .collect::<Vec<FilteredSession>>(),
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn revoke_session_handler(
+    State(data): State<Arc<AppState>>,
+    Path(session_id): Path<i32>,
+    Extension(user): Extension<account::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let revoked = Mutation::revoke_session(&data.db, user.id, session_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if !revoked {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Session not found",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
+
+/// Signs the user out everywhere, including the session making the request
+pub async fn revoke_all_sessions_handler(
+    State(data): State<Arc<AppState>>,
+    Extension(user): Extension<account::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let revoked = Mutation::revoke_account_sessions(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "revoked": revoked,
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn verify_email_handler(
+    State(data): State<Arc<AppState>>,
+    Json(body): Json<VerifyEmailSchema>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let invalid_token = || {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Invalid or expired verification token provided",
+        });
+        (StatusCode::CONFLICT, Json(error_response))
+    };
+
+    let claims = decode_verification_token(data.env.jwt_secret.as_ref(), &body.token)
+        .ok_or_else(invalid_token)?;
+
+    let user = Account::find_by_id(claims.sub)
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    // A link sent to an address the account no longer uses verifies nothing
+    let Some(user) = user.filter(|user| user.email == claims.email) else {
+        return Err(invalid_token());
+    };
+
+    if !user.email_verified {
+        Mutation::verify_email(&data.db, user.id)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+    }
+
+    let json_response = serde_json::json!({
+        "status":  "success",
+        "data": {
+            "message": "Email address has been verified",
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn resend_verification_handler(
+    State(data): State<Arc<AppState>>,
+    Extension(user): Extension<account::Model>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if user.email_verified {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Email address is already verified",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    if let Some(sent_date) = user.verification_email_date {
+        if sent_date + chrono::Duration::minutes(EMAIL_VERIFICATION_RESEND_DELAY_MINUTES) > now {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "A previous verification email was sent too recently",
+            });
+            return Err((StatusCode::CONFLICT, Json(error_response)));
+        }
+    }
+
+    send_verification_email(&data, &user).await?;
+
+    let json_response = serde_json::json!({
+        "status":  "success",
+        "data": {
+            "message": "Check your email for a link to verify your address",
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
 pub async fn inquire_password_reset_handler(
-    Extension(state): Extension<SharedState>,
     State(data): State<Arc<AppState>>,
     Json(body): Json<InquirePasswordResetSchema>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
@@ -207,21 +698,30 @@ pub async fn inquire_password_reset_handler(
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
 
-    if let None = user_exists {
+    let Some(user) = user_exists else {
         let error_response = serde_json::json!({
             "status": "fail",
             "message": "User with that email could not be found",
         });
         return Err((StatusCode::CONFLICT, Json(error_response)));
-    }
+    };
 
-    let user = user_exists.unwrap();
-    let mut db_state = state.lock().await;
-    let now = chrono::Utc::now();
-    let now = now.timestamp() as usize;
+    let latest_token = Query::find_latest_password_reset_token(&data.db, user.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
 
-    if let Some(req_time) = db_state.reset_requests.get_request(&user.email) {
-        if (req_time + PASSWWORD_RESET_REQUEST_DELAY) > now {
+    let now = chrono::Utc::now().naive_utc();
+    if let Some(latest_token) = latest_token {
+        if latest_token.creation_date
+            + chrono::Duration::minutes(PASSWORD_RESET_REQUEST_DELAY_MINUTES)
+            > now
+        {
             let error_response = serde_json::json!({
                 "status": "fail",
                 "message": "A previous reset request has occured too recently",
@@ -230,11 +730,16 @@ pub async fn inquire_password_reset_handler(
         }
     }
 
-    let reset_token = Uuid::new_v4().to_string();
-    db_state
-        .reset_tokens
-        .add_token(reset_token.clone(), user.id);
-    db_state.reset_requests.add_request(user.email.clone(), now);
+    let reset_token = generate_token();
+    Mutation::create_password_reset_token(&data.db, user.id, hash_token(&reset_token))
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
 
     let reset_url = format!(
         "http://{}/auth/resetpassword?token={}",
@@ -254,22 +759,9 @@ pub async fn inquire_password_reset_handler(
 }
 
 pub async fn change_password_handler(
-    Extension(state): Extension<SharedState>,
     State(data): State<Arc<AppState>>,
     Json(body): Json<ResetPasswordSchema>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    let mut other_state = state.lock().await;
-
-    let user_id = other_state.reset_tokens.get_token(&body.token);
-
-    if user_id.is_none() {
-        let error_response = serde_json::json!({
-            "status": "fail",
-            "message": "Invalid reset token provided",
-        });
-        return Err((StatusCode::CONFLICT, Json(error_response)));
-    }
-
     let salt = SaltString::generate(&mut OsRng);
     let hashed_password = Argon2::default()
         .hash_password(body.password.as_bytes(), &salt)
@@ -282,21 +774,23 @@ pub async fn change_password_handler(
         })
         .map(|hash| hash.to_string())?;
 
-    let updated_account = account::ActiveModel {
-        id: ActiveValue::Set(user_id.unwrap()),
-        password: ActiveValue::Set(hashed_password),
-        ..Default::default()
-    };
+    let account = Mutation::reset_password(&data.db, &hash_token(&body.token), hashed_password)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
 
-    let _ = updated_account.update(&data.db).await.map_err(|e| {
+    if account.is_none() {
         let error_response = serde_json::json!({
             "status": "fail",
-            "message": format!("Database error: {}", e),
+            "message": "Invalid or expired reset token provided",
         });
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-    });
-
-    other_state.reset_tokens.remove_token(&body.token);
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
 
     let json_response = serde_json::json!({
         "status":  "success",
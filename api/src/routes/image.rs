@@ -0,0 +1,347 @@
+use crate::{
+    request::{NewProductImages, ProductImageOrder, UpdatedProductImage},
+    response::FilteredStoredObject,
+    routes::product::{
+        filter_product_image_record, find_image_variants, find_product, invalid_product,
+        parse_product_images,
+    },
+    storage::delete_image,
+    tasks::{self, find_orphaned_images},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use entity::*;
+use rust_ecom_service_core::{
+    catalog::ProductImageInput, sea_orm::prelude::Uuid, storage::StoredObject, AppState, Mutation,
+    Query as CoreQuery,
+};
+use std::sync::Arc;
+
+async fn find_live_images(
+    data: &Arc<AppState>,
+    product: &product::Model,
+) -> Result<Vec<product_image::Model>, (StatusCode, Json<serde_json::Value>)> {
+    CoreQuery::find_product_revision(&data.db, product.id, product.revision_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .map(|revision| revision.images)
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product revision could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+fn parse_image_id(image_id: &str) -> Result<Uuid, (StatusCode, Json<serde_json::Value>)> {
+    Uuid::parse_str(image_id)
+        .map_err(|_| invalid_product(format!("Invalid image id provided: {}", image_id)))
+}
+
+/// Images already on a product keep their alt text unless it is given again
+fn unchanged_images(images: &[product_image::Model]) -> Vec<ProductImageInput> {
+    images
+        .iter()
+        .map(|image| ProductImageInput {
+            hash_id: image.hash_id,
+            alt_text: None,
+        })
+        .collect()
+}
+
+async fn images_response(
+    data: &Arc<AppState>,
+    images: Vec<product_image::Model>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let image_variants = find_image_variants(data, &images).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "results": images.len(),
+        "images": images.iter().map(|image| filter_product_image_record(data.storage.as_ref(), image, &image_variants)).collect::<Vec<_>>(),
+    })))
+}
+
+/// Publishes the new gallery and responds with it
+async fn save_images(
+    data: &Arc<AppState>,
+    product: product::Model,
+    images: Vec<ProductImageInput>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let product = Mutation::update_product_images(&data.db, product, images)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let images = find_live_images(data, &product).await?;
+
+    images_response(data, images).await
+}
+
+pub async fn list_product_images(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let images = find_live_images(&data, &product).await?;
+
+    images_response(&data, images).await
+}
+
+/// Adds uploaded images to a product's gallery, at `position` or after the existing ones
+pub async fn attach_product_images(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_images): Json<NewProductImages>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let existing = find_live_images(&data, &product).await?;
+    let new_images = parse_product_images(req_images.images)?;
+
+    if let Some(image) = new_images.iter().find(|image| {
+        existing
+            .iter()
+            .any(|existing| existing.hash_id == image.hash_id)
+    }) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Image {} is already attached to this product", image.hash_id),
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let mut images = unchanged_images(&existing);
+    let position = req_images
+        .position
+        .unwrap_or(images.len())
+        .min(images.len());
+    images.splice(position..position, new_images);
+
+    Ok((
+        StatusCode::CREATED,
+        save_images(&data, product, images).await?,
+    ))
+}
+
+/// Reorders the gallery, which must list every attached image exactly once
+pub async fn reorder_product_images(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_order): Json<ProductImageOrder>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let existing = find_live_images(&data, &product).await?;
+
+    let mut images = vec![];
+    for image_id in &req_order.images {
+        let hash_id = parse_image_id(image_id)?;
+        if images
+            .iter()
+            .any(|image: &ProductImageInput| image.hash_id == hash_id)
+            || !existing.iter().any(|existing| existing.hash_id == hash_id)
+        {
+            return Err(invalid_product(format!(
+                "Image {} is not attached to this product or is listed more than once",
+                image_id
+            )));
+        }
+
+        images.push(ProductImageInput {
+            hash_id,
+            alt_text: None,
+        });
+    }
+
+    if images.len() != existing.len() {
+        return Err(invalid_product(
+            "The new order must list every image attached to the product".to_string(),
+        ));
+    }
+
+    save_images(&data, product, images).await
+}
+
+pub async fn update_product_image(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, image_id)): Path<(String, String)>,
+    Json(req_image): Json<UpdatedProductImage>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let existing = find_live_images(&data, &product).await?;
+    let hash_id = parse_image_id(&image_id)?;
+
+    if !existing.iter().any(|image| image.hash_id == hash_id) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Image is not attached to this product",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
+
+    let images = unchanged_images(&existing)
+        .into_iter()
+        .map(|image| ProductImageInput {
+            alt_text: if image.hash_id == hash_id {
+                Some(req_image.alt_text.to_owned().unwrap_or_default())
+            } else {
+                image.alt_text
+            },
+            ..image
+        })
+        .collect();
+
+    save_images(&data, product, images).await
+}
+
+/// Removes an image from a product's gallery, leaving the upload itself in place
+pub async fn detach_product_image(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, image_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let existing = find_live_images(&data, &product).await?;
+    let hash_id = parse_image_id(&image_id)?;
+
+    if !existing.iter().any(|image| image.hash_id == hash_id) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Image is not attached to this product",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
+
+    let images = unchanged_images(&existing)
+        .into_iter()
+        .filter(|image| image.hash_id != hash_id)
+        .collect();
+
+    save_images(&data, product, images).await
+}
+
+/// Deletes an uploaded image from storage once no product revision or variant shows it
+pub async fn delete_uploaded_image(
+    State(data): State<Arc<AppState>>,
+    Path(image_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let hash_id = parse_image_id(&image_id)?;
+
+    let usage = CoreQuery::find_image_usage(&data.db, hash_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if usage > 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Image is still used by {} product revisions or variants", usage),
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let variants = CoreQuery::find_image_variants(&data.db, vec![hash_id])
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .remove(&hash_id)
+        .unwrap_or_default();
+    for variant in &variants {
+        delete_image(data.storage.as_ref(), &variant.key).await?;
+    }
+    delete_image(data.storage.as_ref(), &hash_id.to_string()).await?;
+
+    Mutation::delete_media(&data.db, vec![hash_id])
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
+
+fn filter_stored_object_record(
+    data: &Arc<AppState>,
+    object: &StoredObject,
+) -> FilteredStoredObject {
+    FilteredStoredObject {
+        key: object.key.to_owned(),
+        url: data.storage.public_url(&object.key),
+        size: object.size,
+        last_modified: object.last_modified,
+    }
+}
+
+fn orphaned_images_response(
+    data: &Arc<AppState>,
+    orphans: &[StoredObject],
+) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "success",
+        "results": orphans.len(),
+        "totalSize": orphans.iter().map(|object| object.size).sum::<u64>(),
+        "graceHours": data.env.image_cleanup_grace_hours,
+        "images": orphans.iter().map(|object| filter_stored_object_record(data, object)).collect::<Vec<_>>(),
+    }))
+}
+
+fn image_cleanup_error(e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "fail",
+        "message": format!("Image cleanup error: {}", e),
+    });
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+}
+
+/// Dry run of the orphaned image cleanup, listing the files it would delete
+pub async fn orphaned_images_report(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let orphans = find_orphaned_images(&data)
+        .await
+        .map_err(image_cleanup_error)?;
+
+    Ok(orphaned_images_response(&data, &orphans))
+}
+
+/// Runs the orphaned image cleanup now instead of waiting for the background task
+pub async fn delete_orphaned_images(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let orphans = find_orphaned_images(&data)
+        .await
+        .map_err(image_cleanup_error)?;
+    let deleted = tasks::delete_orphaned_images(&data, orphans)
+        .await
+        .map_err(image_cleanup_error)?;
+
+    Ok(orphaned_images_response(&data, &deleted))
+}
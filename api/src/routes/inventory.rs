@@ -0,0 +1,571 @@
+use crate::{
+    email::send_restock_notification_email,
+    request::{
+        NewLocation, NewRestockSubscription, NewStockReceipt, NewStockTransfer, UpdatedLocation,
+        UpdatedLowStockThreshold,
+    },
+    response::FilteredLocation,
+};
+use axum::{
+    extract::{Path, Query as AxumQuery, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use entity::{prelude::*, *};
+use rust_ecom_service_core::{
+    catalog::is_product_visible,
+    inventory::{is_back_in_stock, TransferOutcome, STOCK_KIND_RECEIPT},
+    sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QueryOrder},
+    AppState, Mutation, Query,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn filter_location_record(location: &location::Model) -> FilteredLocation {
+    FilteredLocation {
+        id: location.id,
+        label: location.label.to_owned(),
+        priority: location.priority,
+    }
+}
+
+fn parse_product_id(product_id: &str) -> Result<i32, (StatusCode, Json<serde_json::Value>)> {
+    product_id.parse::<i32>().map_err(|_| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Invalid product id provided",
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })
+}
+
+async fn find_product(
+    data: &Arc<AppState>,
+    product_id: i32,
+) -> Result<product::Model, (StatusCode, Json<serde_json::Value>)> {
+    Product::find_by_id(product_id)
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+async fn find_location(
+    data: &Arc<AppState>,
+    location_id: Option<i32>,
+) -> Result<location::Model, (StatusCode, Json<serde_json::Value>)> {
+    let location = match location_id {
+        Some(location_id) => Location::find_by_id(location_id).one(&data.db).await,
+        None => {
+            Location::find()
+                .order_by_asc(location::Column::Priority)
+                .order_by_asc(location::Column::Id)
+                .one(&data.db)
+                .await
+        }
+    };
+
+    location
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Location could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+async fn find_variant(
+    data: &Arc<AppState>,
+    product_id: i32,
+    variant_id: Option<i32>,
+) -> Result<Option<product_variant::Model>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(variant_id) = variant_id else {
+        return Ok(None);
+    };
+
+    Query::find_product_variant(&data.db, product_id, variant_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .map(Some)
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product variant could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+/// Stock that can still be sold, orders that have yet to ship hold theirs back
+async fn find_availability(
+    data: &Arc<AppState>,
+    product_id: i32,
+) -> Result<i64, (StatusCode, Json<serde_json::Value>)> {
+    Query::find_product_availability(&data.db, product_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })
+}
+
+pub async fn receive_stock(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_stock): Json<NewStockReceipt>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if req_stock.amount <= 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Stock receipts must contain a positive amount",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let product = find_product(&data, parse_product_id(&product_id)?).await?;
+    let variant = find_variant(&data, product.id, req_stock.variant_id).await?;
+    let location = find_location(&data, req_stock.location_id).await?;
+    let previous_available = find_availability(&data, product.id).await?;
+
+    let new_stock = stock::ActiveModel {
+        product_id: ActiveValue::Set(product.id),
+        variant_id: ActiveValue::Set(variant.as_ref().map(|variant| variant.id)),
+        location_id: ActiveValue::Set(location.id),
+        amount: ActiveValue::Set(req_stock.amount),
+        kind: ActiveValue::Set(STOCK_KIND_RECEIPT.to_owned()),
+        addition_date: ActiveValue::Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    Stock::insert(new_stock)
+        .exec_with_returning(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let available = previous_available + req_stock.amount as i64;
+
+    if is_back_in_stock(previous_available, req_stock.amount as i64)
+        && product.allow_restock_notifications
+    {
+        tokio::spawn(notify_restock_subscribers(data.clone(), product.clone()));
+    }
+
+    let stock_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "productId": product.id,
+        "variantId": variant.map(|variant| variant.id),
+        "locationId": location.id,
+        "available": available,
+    })});
+
+    Ok(Json(stock_response))
+}
+
+async fn notify_restock_subscribers(data: Arc<AppState>, product: product::Model) {
+    let product_detail = match ProductDetail::find_by_id(product.revision_id)
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(product_detail)) => product_detail,
+        Ok(None) => return,
+        Err(e) => {
+            println!("Unable to locate product for restock notifications: {}", e);
+            return;
+        }
+    };
+
+    let subscriptions = match RestockSubscription::find()
+        .filter(restock_subscription::Column::ProductId.eq(product.id))
+        .filter(restock_subscription::Column::FulfilledDate.is_null())
+        .all(&data.db)
+        .await
+    {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            println!("Unable to retrieve restock subscriptions: {}", e);
+            return;
+        }
+    };
+
+    let product_url = format!(
+        "http://{}/product/{}",
+        &data.env.web_host, product.short_url
+    );
+    let notification_email_content = format!(
+        "<a href='{}'>{}</a> is back in stock",
+        product_url, product_detail.name
+    );
+
+    for subscription in subscriptions {
+        send_restock_notification_email(&subscription.email, &notification_email_content).await;
+
+        let fulfilled_subscription = restock_subscription::ActiveModel {
+            id: ActiveValue::Set(subscription.id),
+            fulfilled_date: ActiveValue::Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        };
+
+        if let Err(e) = fulfilled_subscription.update(&data.db).await {
+            println!("Unable to fulfill restock subscription: {}", e);
+        }
+    }
+}
+
+pub async fn subscribe_to_restock(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_subscription): Json<NewRestockSubscription>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let email = req_subscription.email.trim().to_ascii_lowercase();
+
+    if !email.contains('@') {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Invalid email address provided",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let product = find_product(&data, parse_product_id(&product_id)?).await?;
+
+    if !is_product_visible(&product, Utc::now().naive_utc()) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product could not be found",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
+
+    if !product.allow_restock_notifications {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Restock notifications are not available for this product",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    if find_availability(&data, product.id).await? > 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product is currently in stock",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let existing_subscription = RestockSubscription::find()
+        .filter(restock_subscription::Column::ProductId.eq(product.id))
+        .filter(restock_subscription::Column::Email.eq(email.to_owned()))
+        .filter(restock_subscription::Column::FulfilledDate.is_null())
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if existing_subscription.is_none() {
+        let new_subscription = restock_subscription::ActiveModel {
+            product_id: ActiveValue::Set(product.id),
+            email: ActiveValue::Set(email),
+            creation_date: ActiveValue::Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        RestockSubscription::insert(new_subscription)
+            .exec_with_returning(&data.db)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+    }
+
+    let json_response = serde_json::json!({
+        "status":  "success",
+        "data": {
+            "message": "You will be notified when this product is back in stock",
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn update_low_stock_threshold(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_threshold): Json<UpdatedLowStockThreshold>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if req_threshold.low_stock_threshold.unwrap_or_default() < 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Low stock threshold cannot be negative",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let product = find_product(&data, parse_product_id(&product_id)?).await?;
+
+    let updated_product = product::ActiveModel {
+        id: ActiveValue::Set(product.id),
+        low_stock_threshold: ActiveValue::Set(req_threshold.low_stock_threshold),
+        low_stock_alert_date: ActiveValue::Set(None),
+        ..Default::default()
+    };
+
+    let product = updated_product.update(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let threshold_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "productId": product.id,
+        "lowStockThreshold": product.low_stock_threshold,
+    })});
+
+    Ok(Json(threshold_response))
+}
+
+pub async fn inventory_report(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let report = Query::find_inventory_report(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let report_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "products": report
+    })});
+
+    Ok(Json(report_response))
+}
+
+#[derive(Deserialize)]
+pub struct ProductStockParams {
+    variant_id: Option<i32>,
+}
+
+pub async fn product_stock(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    AxumQuery(params): AxumQuery<ProductStockParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, parse_product_id(&product_id)?).await?;
+    let variant_id = find_variant(&data, product.id, params.variant_id)
+        .await?
+        .map(|variant| variant.id);
+
+    let locations = Query::find_product_location_inventory(&data.db, product.id, variant_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let stock_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "productId": product.id,
+        "variantId": variant_id,
+        "locations": locations,
+    })});
+
+    Ok(Json(stock_response))
+}
+
+pub async fn transfer_stock(
+    State(data): State<Arc<AppState>>,
+    Json(req_transfer): Json<NewStockTransfer>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if req_transfer.amount <= 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Stock transfers must contain a positive amount",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    if req_transfer.from_location_id == req_transfer.to_location_id {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Stock cannot be transferred to the location it is leaving",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let product = find_product(&data, req_transfer.product_id).await?;
+    let variant_id = find_variant(&data, product.id, req_transfer.variant_id)
+        .await?
+        .map(|variant| variant.id);
+    let from_location = find_location(&data, Some(req_transfer.from_location_id)).await?;
+    let to_location = find_location(&data, Some(req_transfer.to_location_id)).await?;
+
+    let transfer_id = Uuid::new_v4();
+    let outcome = Mutation::transfer_stock(
+        &data.db,
+        transfer_id,
+        product.id,
+        variant_id,
+        from_location.id,
+        to_location.id,
+        req_transfer.amount,
+    )
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    if let TransferOutcome::Insufficient(available) = outcome {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Only {} available to transfer from {}", available, from_location.label),
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let transfer_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "transferId": transfer_id,
+        "productId": product.id,
+        "variantId": variant_id,
+        "fromLocationId": from_location.id,
+        "toLocationId": to_location.id,
+        "amount": req_transfer.amount,
+    })});
+
+    Ok(Json(transfer_response))
+}
+
+pub async fn list_locations(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let locations = Query::find_locations(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let locations_response = serde_json::json!({"locations": locations.iter().map(filter_location_record).collect::<Vec<_>>()});
+
+    Ok(Json(locations_response))
+}
+
+pub async fn create_location(
+    State(data): State<Arc<AppState>>,
+    Json(req_location): Json<NewLocation>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let new_location = location::ActiveModel {
+        id: ActiveValue::NotSet,
+        label: ActiveValue::Set(req_location.label),
+        priority: ActiveValue::Set(req_location.priority),
+    };
+
+    let location = Location::insert(new_location)
+        .exec_with_returning(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let location_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "location": filter_location_record(&location)
+    })});
+
+    Ok(Json(location_response))
+}
+
+pub async fn update_location(
+    State(data): State<Arc<AppState>>,
+    Path(location_id): Path<String>,
+    Json(req_location): Json<UpdatedLocation>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let location_id = location_id.parse::<i32>().map_err(|_| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Invalid location id provided",
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+    let location = find_location(&data, Some(location_id)).await?;
+
+    let mut updated_location: location::ActiveModel = location.into();
+    if let Some(label) = req_location.label {
+        updated_location.label = ActiveValue::Set(label);
+    }
+    if let Some(priority) = req_location.priority {
+        updated_location.priority = ActiveValue::Set(priority);
+    }
+
+    let location = updated_location.update(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let location_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "location": filter_location_record(&location)
+    })});
+
+    Ok(Json(location_response))
+}
@@ -1,4 +1,10 @@
+pub mod account;
 pub mod auth;
 pub mod content;
+pub mod image;
+pub mod inventory;
 pub mod orders;
 pub mod product;
+pub mod revision;
+pub mod role;
+pub mod variant;
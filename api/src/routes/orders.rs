@@ -10,15 +10,19 @@ use http::StatusCode;
 use rand::Rng;
 use rust_decimal::{prelude::FromPrimitive, Decimal};
 use rust_ecom_service_core::{
+    catalog::is_product_visible,
     ecommerce::{Customer, Invoice, OrderAdjustments},
     payment_processing::{authorize_net, manager::ChargeCreditCardRequest},
-    sea_orm::{ActiveValue, EntityTrait},
-    AppState,
+    sea_orm::{ActiveValue, ColumnTrait, EntityTrait, QueryFilter},
+    AppState, Query,
 };
 use serde_json::json;
 use std::{convert::Infallible, sync::Arc, time::Duration};
 
-use crate::{priveleges::check_admin, request::NewOrder};
+use crate::{
+    request::NewOrder,
+    response::{FilteredOrder, FilteredOrderItem},
+};
 
 pub async fn process_order(
     State(data): State<Arc<AppState>>,
@@ -30,6 +34,101 @@ pub async fn process_order(
         .unwrap()
         .push_back(processing_msg.into());
 
+    let mut order_lines = vec![];
+
+    for item in req_order.order_items {
+        let product_id = item.id.parse::<i32>().map_err(|_| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Invalid product id provided: {}", item.id),
+            });
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?;
+
+        // Reservations are summed from order quantities, so anything else would free up stock
+        if item.qty <= 0 {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Order quantities must be positive: {}", item.id),
+            });
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+
+        let product = Product::find_by_id(product_id)
+            .find_also_related(ProductDetail)
+            .one(&data.db)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        let Some((product, Some(product_detail))) =
+            product.filter(|(product, _)| is_product_visible(product, Utc::now().naive_utc()))
+        else {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Product could not be found: {}", item.id),
+            });
+            return Err((StatusCode::NOT_FOUND, Json(error_response)));
+        };
+
+        let variants = ProductVariant::find()
+            .filter(product_variant::Column::ProductId.eq(product.id))
+            .filter(product_variant::Column::Active.eq(true))
+            .all(&data.db)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        let variant = match item.variant_id {
+            Some(variant_id) => {
+                let Some(variant) = variants
+                    .into_iter()
+                    .find(|variant| variant.id == variant_id)
+                else {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Product variant could not be found: {}", variant_id),
+                    });
+                    return Err((StatusCode::NOT_FOUND, Json(error_response)));
+                };
+                Some(variant)
+            }
+            None if !variants.is_empty() => {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("A variant must be selected for product: {}", item.id),
+                });
+                return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+            }
+            None => None,
+        };
+
+        let variant_options = match &variant {
+            Some(variant) => Query::find_variant_options(&data.db, variant.id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?,
+            None => vec![],
+        };
+
+        order_lines.push((item.qty, product, product_detail, variant, variant_options));
+    }
+
     let invoice = Invoice::create(
         vec![],
         OrderAdjustments {
@@ -108,15 +207,33 @@ pub async fn process_order(
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
 
-    for item in req_order.order_items {
+    for (qty, product, product_detail, variant, variant_options) in order_lines {
+        let variant_id = variant.as_ref().map(|variant| variant.id);
+        let location_id = Query::find_fulfillment_location(&data.db, product.id, variant_id, qty)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
         let item = order_item::ActiveModel {
             order_id: ActiveValue::Set(new_order.id),
-            qty: ActiveValue::Set(item.qty),
-            price: ActiveValue::Set(Decimal::from_f32(0.0).unwrap()),
+            product_id: ActiveValue::Set(product_detail.id),
+            location_id: ActiveValue::Set(location_id),
+            variant_id: ActiveValue::Set(variant_id),
+            qty: ActiveValue::Set(qty),
+            price: ActiveValue::Set(
+                variant
+                    .and_then(|variant| variant.price)
+                    .unwrap_or(product_detail.price),
+            ),
             ..Default::default()
         };
 
-        OrderItem::insert(item)
+        let item = OrderItem::insert(item)
             .exec_with_returning(&data.db)
             .await
             .map_err(|e| {
@@ -126,6 +243,26 @@ pub async fn process_order(
                 });
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
             })?;
+
+        for (attribute, attribute_option) in variant_options {
+            let item_attribute = order_item_attribute::ActiveModel {
+                order_item_id: ActiveValue::Set(item.id),
+                label: ActiveValue::Set(attribute.label),
+                value: ActiveValue::Set(Some(attribute_option.label)),
+                ..Default::default()
+            };
+
+            OrderItemAttribute::insert(item_attribute)
+                .exec(&data.db)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+        }
     }
 
     let transaction_req = ChargeCreditCardRequest {
@@ -153,26 +290,67 @@ pub async fn process_order(
     ))
 }
 
-pub async fn list_orders(
+fn filter_order_record(order: &order::Model, items: &[order_item::Model]) -> FilteredOrder {
+    FilteredOrder {
+        id: order.id,
+        status: order.status.to_owned(),
+        email: order.email.to_owned(),
+        tax_amount: order.tax_amount,
+        shipping_amount: order.shipping_amount,
+        total_amount: order.total_amount,
+        creation_date: order.creation_date,
+        items: items
+            .iter()
+            .map(|item| FilteredOrderItem {
+                id: item.id,
+                product_id: item.product_id,
+                variant_id: item.variant_id,
+                price: item.price,
+                qty: item.qty,
+            })
+            .collect(),
+    }
+}
+
+/// Order history of the signed in account. Guest orders placed with its email address are
+/// included, which is why the address has to be verified first.
+pub async fn list_account_orders(
     Extension(user): Extension<account::Model>,
     State(data): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
-    }
+    let orders = Query::find_account_orders(&data.db, &user)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
 
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "orders": orders
+                .iter()
+                .map(|(order, items)| filter_order_record(order, items))
+                .collect::<Vec<FilteredOrder>>(),
+        },
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn list_orders(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     Ok(())
 }
 
 pub async fn live_order_events(
-    Extension(user): Extension<account::Model>,
     State(data): State<Arc<AppState>>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
 {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
-    }
-
     let stream = async_stream::stream! {
         let mut interval = tokio::time::interval(Duration::from_millis(100));
         loop {
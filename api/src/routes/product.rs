@@ -1,24 +1,41 @@
 use crate::{
-    priveleges::check_admin,
-    request::{NewAttribute, NewCategory, NewProduct},
+    request::{NewAttribute, NewCategory, NewProduct, NewProductImage, NewProductRevision},
     response::{
-        FilteredAttribute, FilteredAttributeOption, FilteredCategory, FilteredProductAttribute,
+        FilteredAttribute, FilteredAttributeFacet, FilteredAttributeOption, FilteredAvailability,
+        FilteredCategory, FilteredCategoryFacet, FilteredCategoryNode, FilteredImageVariant,
+        FilteredMedia, FilteredMediaUploader, FilteredOptionFacet, FilteredPriceFacet,
+        FilteredProduct, FilteredProductAttribute, FilteredProductFacets, FilteredProductImage,
+        FilteredProductVariant, FilteredSearchResult, FilteredSimpleProduct, FilteredVariantOption,
     },
-    storage::{convert_image_to_webp, get_uploaded_images, upload_image},
+    storage::{convert_image_to_webp, delete_image, upload_image, validate_image},
 };
 use axum::{
-    extract::{Multipart, Path, Query, State},
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Extension, Json,
 };
+use chrono::Utc;
 use entity::{prelude::*, *};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 use rust_ecom_service_core::{
-    sea_orm::{ActiveValue, EntityTrait},
-    AppState,
+    catalog::{
+        is_product_visible, validate_attribute_content, AttributeInput, AttributeOptionInput,
+        CategoryInput, CategoryNode, ListedProduct, MediaDetails, MediaInput, ProductDetails,
+        ProductFacets, ProductFilters, ProductImageInput, ProductInput, ProductSort, RevisionInput,
+        VariantDetails, ATTRIBUTE_KINDS, DEFAULT_MEDIA_PER_PAGE, DEFAULT_PRODUCTS_PER_PAGE,
+        MAX_MEDIA_PER_PAGE, MAX_PRODUCTS_PER_PAGE, PRODUCT_STATUSES,
+    },
+    sea_orm::{prelude::Uuid, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter},
+    search::build_prefix_query,
+    storage::StorageBackend,
+    AppState, Mutation, Query as CoreQuery,
 };
 use serde::Deserialize;
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 fn filter_attribute_option_record(
     attribute_option: &attribute_option::Model,
@@ -28,6 +45,7 @@ fn filter_attribute_option_record(
         label: attribute_option.label.to_string(),
         content: attribute_option.content.to_string(),
         attribute_id: attribute_option.attribute_id,
+        position: attribute_option.position,
     }
 }
 
@@ -50,19 +68,282 @@ fn filter_category_record(category: &category::Model) -> FilteredCategory {
     FilteredCategory {
         id: category.id,
         label: category.label.to_string(),
-        parent_id: category.parent_id.unwrap_or(-1),
+        parent_id: category.parent_id,
+        position: category.position,
+    }
+}
+
+fn filter_category_node_record(node: &CategoryNode) -> FilteredCategoryNode {
+    FilteredCategoryNode {
+        id: node.category.id,
+        label: node.category.label.to_string(),
+        parent_id: node.category.parent_id,
+        position: node.category.position,
+        children: node
+            .children
+            .iter()
+            .map(filter_category_node_record)
+            .collect(),
     }
 }
 
 fn filter_product_attribute_record(
     product_attribute: &product_attribute::Model,
     attribute: &attribute::Model,
+    attribute_options: &[attribute_option::Model],
 ) -> FilteredProductAttribute {
     FilteredProductAttribute {
         id: attribute.id,
         kind: attribute.kind.to_string(),
         label: attribute.label.to_string(),
         content: product_attribute.content.to_string(),
+        options: attribute_options
+            .iter()
+            .map(filter_attribute_option_record)
+            .collect(),
+    }
+}
+
+pub fn filter_product_variant_record(
+    storage: &dyn StorageBackend,
+    variant_details: &VariantDetails,
+    base_price: Decimal,
+) -> FilteredProductVariant {
+    let images = variant_details
+        .images
+        .iter()
+        .map(|image| storage.public_url(&image.hash_id.to_string()))
+        .collect::<Vec<_>>();
+
+    FilteredProductVariant {
+        id: variant_details.variant.id,
+        sku: variant_details.variant.sku.to_owned(),
+        price: variant_details.variant.price.unwrap_or(base_price),
+        active: variant_details.variant.active,
+        img: images.first().cloned(),
+        images,
+        options: variant_details
+            .options
+            .iter()
+            .map(|(attribute, attribute_option)| FilteredVariantOption {
+                attribute_id: attribute.id,
+                attribute: attribute.label.to_owned(),
+                option_id: attribute_option.id,
+                label: attribute_option.label.to_owned(),
+                content: attribute_option.content.to_owned(),
+            })
+            .collect(),
+        available: variant_details.available,
+    }
+}
+
+pub fn filter_image_variant_record(
+    storage: &dyn StorageBackend,
+    variant: &image_variant::Model,
+) -> FilteredImageVariant {
+    FilteredImageVariant {
+        name: variant.name.to_owned(),
+        url: storage.public_url(&variant.key),
+        width: variant.width,
+        height: variant.height,
+    }
+}
+
+fn image_srcset(variants: &[FilteredImageVariant]) -> String {
+    variants
+        .iter()
+        .map(|variant| format!("{} {}w", variant.url, variant.width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn filter_media_record(storage: &dyn StorageBackend, details: &MediaDetails) -> FilteredMedia {
+    let MediaDetails {
+        media,
+        uploader,
+        variants,
+        usage,
+    } = details;
+    let variants = variants
+        .iter()
+        .map(|variant| filter_image_variant_record(storage, variant))
+        .collect::<Vec<_>>();
+
+    FilteredMedia {
+        id: media.hash_id.to_string(),
+        url: storage.public_url(&media.hash_id.to_string()),
+        file_name: media.file_name.to_owned(),
+        content_type: media.content_type.to_owned(),
+        width: media.width,
+        height: media.height,
+        size: media.size,
+        uploaded_by: uploader.as_ref().map(|uploader| FilteredMediaUploader {
+            id: uploader.id,
+            name: uploader.name.to_owned(),
+        }),
+        upload_date: media.creation_date,
+        usage: *usage,
+        srcset: image_srcset(&variants),
+        variants,
+    }
+}
+
+pub fn filter_product_image_record(
+    storage: &dyn StorageBackend,
+    image: &product_image::Model,
+    image_variants: &HashMap<Uuid, Vec<image_variant::Model>>,
+) -> FilteredProductImage {
+    let variants = image_variants
+        .get(&image.hash_id)
+        .map(|variants| {
+            variants
+                .iter()
+                .map(|variant| filter_image_variant_record(storage, variant))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let srcset = image_srcset(&variants);
+
+    FilteredProductImage {
+        id: image.hash_id.to_string(),
+        url: storage.public_url(&image.hash_id.to_string()),
+        alt_text: image.alt_text.to_owned(),
+        position: image.position,
+        variants,
+        srcset,
+    }
+}
+
+fn filter_product_record(
+    storage: &dyn StorageBackend,
+    product_details: &ProductDetails,
+    image_variants: &HashMap<Uuid, Vec<image_variant::Model>>,
+) -> FilteredProduct {
+    let ProductDetails {
+        product,
+        detail,
+        images,
+        categories,
+        attributes,
+        available,
+        variants,
+    } = product_details;
+    let image_details = images
+        .iter()
+        .map(|image| filter_product_image_record(storage, image, image_variants))
+        .collect::<Vec<_>>();
+    let images = image_details
+        .iter()
+        .map(|image| image.url.to_owned())
+        .collect::<Vec<_>>();
+
+    FilteredProduct {
+        id: product.id,
+        short_url: product.short_url.to_string(),
+        status: product.status.to_owned(),
+        publish_date: product.publish_date,
+        unpublish_date: product.unpublish_date,
+        name: detail.name.to_string(),
+        description: detail.description.to_string(),
+        price: detail.price,
+        upc: detail.upc.to_owned(),
+        real_weight: detail.real_weight,
+        ship_weight: detail.ship_weight,
+        img: images.first().cloned(),
+        images,
+        image_details,
+        categories: categories.iter().map(filter_category_record).collect(),
+        primary_category_id: product.category_id,
+        attributes: attributes
+            .iter()
+            .map(|(product_attribute, attribute, attribute_options)| {
+                filter_product_attribute_record(product_attribute, attribute, attribute_options)
+            })
+            .collect(),
+        variants: variants
+            .iter()
+            .map(|variant| filter_product_variant_record(storage, variant, detail.price))
+            .collect(),
+        availability: FilteredAvailability {
+            in_stock: *available > 0,
+            available: *available,
+            allow_back_order: product.allow_back_order,
+            allow_restock_notifications: product.allow_restock_notifications,
+        },
+    }
+}
+
+fn filter_simple_product_record(
+    storage: &dyn StorageBackend,
+    product: &ListedProduct,
+    image: &Option<Uuid>,
+) -> FilteredSimpleProduct {
+    FilteredSimpleProduct {
+        id: product.id,
+        short_url: product.short_url.to_string(),
+        name: product.name.to_string(),
+        price: product.price,
+        img: image.map(|image| storage.public_url(&image.to_string())),
+        category_id: product.category_id,
+        category: product.category_label.to_owned(),
+    }
+}
+
+fn filter_product_facets_record(facets: &ProductFacets) -> FilteredProductFacets {
+    FilteredProductFacets {
+        categories: facets
+            .categories
+            .iter()
+            .map(|(category, count)| FilteredCategoryFacet {
+                id: category.id,
+                label: category.label.to_string(),
+                parent_id: category.parent_id,
+                count: *count,
+            })
+            .collect(),
+        attributes: facets
+            .attributes
+            .iter()
+            .map(|facet| FilteredAttributeFacet {
+                id: facet.attribute.id,
+                label: facet.attribute.label.to_string(),
+                kind: facet.attribute.kind.to_string(),
+                options: facet
+                    .options
+                    .iter()
+                    .map(|(option, count)| FilteredOptionFacet {
+                        id: option.id,
+                        label: option.label.to_string(),
+                        content: option.content.to_string(),
+                        count: *count,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        prices: facets
+            .prices
+            .iter()
+            .map(|price| FilteredPriceFacet {
+                min_price: price.min,
+                max_price: price.max,
+                count: price.count,
+            })
+            .collect(),
+    }
+}
+
+fn parse_price(
+    price: Option<f32>,
+) -> Result<Option<Decimal>, (StatusCode, Json<serde_json::Value>)> {
+    match price {
+        Some(price) => Decimal::from_f32(price).map(Some).ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Invalid price provided",
+            });
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        }),
+        None => Ok(None),
     }
 }
 
@@ -70,77 +351,244 @@ fn filter_product_attribute_record(
 pub struct ProductRetrievalParams {
     page: Option<u64>,
     posts_per_page: Option<u64>,
+    sort: Option<String>,
+    category: Option<i32>,
+    min_price: Option<f32>,
+    max_price: Option<f32>,
+    in_stock: Option<bool>,
+    /// Comma separated attribute option ids
+    options: Option<String>,
+}
+
+fn parse_option_ids(
+    options: &Option<String>,
+) -> Result<Vec<i32>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(options) = options else {
+        return Ok(vec![]);
+    };
+
+    options
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(|option| {
+            option.parse::<i32>().map_err(|_| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Invalid attribute option provided: {}", option),
+                });
+                (StatusCode::BAD_REQUEST, Json(error_response))
+            })
+        })
+        .collect()
+}
+
+async fn product_filters(
+    data: &Arc<AppState>,
+    params: &ProductRetrievalParams,
+) -> Result<ProductFilters, (StatusCode, Json<serde_json::Value>)> {
+    let category_ids = match params.category {
+        Some(category_id) => Some(
+            CoreQuery::find_category_tree_ids(&data.db, category_id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?,
+        ),
+        None => None,
+    };
+
+    Ok(ProductFilters {
+        category_ids,
+        min_price: parse_price(params.min_price)?,
+        max_price: parse_price(params.max_price)?,
+        in_stock: params.in_stock.unwrap_or(false),
+        option_ids: parse_option_ids(&params.options)?,
+    })
 }
 
 pub async fn all_products(
-    state: State<Arc<AppState>>,
+    State(data): State<Arc<AppState>>,
     Query(params): Query<ProductRetrievalParams>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
+    let page = params.page.unwrap_or(1).max(1);
+    let posts_per_page = params
+        .posts_per_page
+        .unwrap_or(DEFAULT_PRODUCTS_PER_PAGE)
+        .clamp(1, MAX_PRODUCTS_PER_PAGE);
+
+    let sort = match &params.sort {
+        Some(sort) => ProductSort::parse(sort).ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Invalid sort provided: {}", sort),
+            });
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?,
+        None => ProductSort::default(),
+    };
+
+    let filters = product_filters(&data, &params).await?;
+
+    let product_page =
+        CoreQuery::find_products_in_page(&data.db, page, posts_per_page, &filters, sort)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+    let products_response = serde_json::json!({
+        "products": product_page.products.iter().map(|(product, image)| filter_simple_product_record(data.storage.as_ref(), product, image)).collect::<Vec<_>>(),
+        "page": page,
+        "postsPerPage": posts_per_page,
+        "totalItems": product_page.total_items,
+        "totalPages": product_page.total_pages,
+    });
+
+    Ok(Json(products_response))
 }
 
-pub async fn list_product(
+pub async fn product_facets(
     State(data): State<Arc<AppState>>,
-    Path(product_id): Path<String>,
+    Query(params): Query<ProductRetrievalParams>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
+    let filters = product_filters(&data, &params).await?;
+
+    let facets = CoreQuery::find_product_facets(&data.db, &filters)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let facets_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "facets": filter_product_facets_record(&facets),
+        }
+    });
+
+    Ok(Json(facets_response))
 }
 
-pub async fn create_product(
-    Extension(user): Extension<account::Model>,
-    State(data): State<Arc<AppState>>,
-    Json(req_product): Json<NewProduct>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
+#[derive(Deserialize)]
+pub struct ProductSearchParams {
+    q: Option<String>,
+    page: Option<u64>,
+    posts_per_page: Option<u64>,
 }
 
-pub async fn upload_product_image(
-    Extension(user): Extension<account::Model>,
-    mut files: Multipart,
+pub async fn search_products(
+    State(data): State<Arc<AppState>>,
+    Query(params): Query<ProductSearchParams>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
-    }
-
-    let mut file_locations: HashSet<String> = HashSet::new();
+    let page = params.page.unwrap_or(1).max(1);
+    let posts_per_page = params
+        .posts_per_page
+        .unwrap_or(DEFAULT_PRODUCTS_PER_PAGE)
+        .clamp(1, MAX_PRODUCTS_PER_PAGE);
 
-    while let Some(file) = files.next_field().await.unwrap() {
-        let data = file.bytes().await.unwrap();
-        let image = convert_image_to_webp(data.as_ref()).to_owned();
+    let search_query =
+        build_prefix_query(params.q.as_deref().unwrap_or_default()).ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "A search query is required",
+            });
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?;
 
-        let s3_resp = upload_image(image).await.unwrap();
-        file_locations.insert(s3_resp);
-    }
+    let search_page = CoreQuery::search_products(&data.db, &search_query, page, posts_per_page)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
 
-    let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
-        "files": file_locations
-    })});
+    let search_response = serde_json::json!({
+        "products": search_page
+            .results
+            .iter()
+            .map(|(result, image)| FilteredSearchResult {
+                id: result.id,
+                short_url: result.short_url.to_owned(),
+                name: result.name.to_owned(),
+                price: result.price,
+                img: image.map(|image| data.storage.public_url(&image.to_string())),
+                snippet: result.snippet.to_owned(),
+                rank: result.rank,
+            })
+            .collect::<Vec<_>>(),
+        "page": page,
+        "postsPerPage": posts_per_page,
+        "totalItems": search_page.total_items,
+        "totalPages": search_page.total_pages,
+    });
 
-    Ok(Json(user_response))
+    Ok(Json(search_response))
 }
 
-pub async fn list_uploaded_images(
-    Extension(user): Extension<account::Model>,
+pub async fn list_product(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
-    }
+    let product = find_product(&data, &product_id).await?;
 
-    let file_locations = get_uploaded_images().await.unwrap();
+    if !is_product_visible(&product, Utc::now().naive_utc()) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product could not be found",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
 
-    let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
-        "images": file_locations
+    let product_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "product": find_filtered_product(&data, product).await?
     })});
 
-    Ok(Json(user_response))
+    Ok(Json(product_response))
 }
 
-pub async fn list_attributes(
-    State(data): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    let all_attributes = Attribute::find()
-        .find_with_related(AttributeOption)
-        .all(&data.db)
+pub async fn find_product(
+    data: &Arc<AppState>,
+    product_id: &str,
+) -> Result<product::Model, (StatusCode, Json<serde_json::Value>)> {
+    CoreQuery::find_product_by_id_or_short_url(&data.db, product_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+pub async fn find_filtered_product(
+    data: &Arc<AppState>,
+    product: product::Model,
+) -> Result<FilteredProduct, (StatusCode, Json<serde_json::Value>)> {
+    let product_details = CoreQuery::find_product_details(&data.db, product)
         .await
         .map_err(|e| {
             let error_response = serde_json::json!({
@@ -149,41 +597,94 @@ pub async fn list_attributes(
             });
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
+    let image_variants = find_image_variants(data, &product_details.images).await?;
 
-    let attributes_response = serde_json::json!({"attributes": all_attributes.iter().map(|attribute| filter_attribute_record(&attribute.0, attribute.1.to_owned())).collect::<Vec<_>>()});
+    Ok(filter_product_record(
+        data.storage.as_ref(),
+        &product_details,
+        &image_variants,
+    ))
+}
 
-    Ok(Json(attributes_response))
+/// Loads the resized copies of a gallery's images
+pub async fn find_image_variants(
+    data: &Arc<AppState>,
+    images: &[product_image::Model],
+) -> Result<HashMap<Uuid, Vec<image_variant::Model>>, (StatusCode, Json<serde_json::Value>)> {
+    let hash_ids = images.iter().map(|image| image.hash_id).collect();
+    CoreQuery::find_image_variants(&data.db, hash_ids)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })
 }
 
-pub async fn retrieve_attribute(
-    State(data): State<Arc<AppState>>,
-    Path(attribute_id): Path<String>,
-    Extension(user): Extension<account::Model>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
+pub fn invalid_product(message: String) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "fail",
+        "message": message,
+    });
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
+
+/// Parses image ids into a gallery, rejecting malformed and repeated images
+pub fn parse_product_images(
+    images: Vec<NewProductImage>,
+) -> Result<Vec<ProductImageInput>, (StatusCode, Json<serde_json::Value>)> {
+    let mut parsed: Vec<ProductImageInput> = vec![];
+
+    for image in images {
+        let (image_id, alt_text) = match image {
+            NewProductImage::Id(id) => (id, None),
+            NewProductImage::Described { id, alt_text } => (id, alt_text),
+        };
+
+        let hash_id = Uuid::parse_str(&image_id)
+            .map_err(|_| invalid_product(format!("Invalid image id provided: {}", image_id)))?;
+
+        if parsed.iter().any(|image| image.hash_id == hash_id) {
+            return Err(invalid_product(format!(
+                "Image {} is listed more than once",
+                image_id
+            )));
+        }
+
+        parsed.push(ProductImageInput { hash_id, alt_text });
     }
 
-    Ok(())
+    Ok(parsed)
 }
 
-pub async fn create_attribute(
-    Extension(user): Extension<account::Model>,
-    State(data): State<Arc<AppState>>,
-    Json(req_attribute): Json<NewAttribute>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
+/// Checks the content saved with each revision: name, price, attribute values and images
+pub async fn validate_product_revision(
+    data: &Arc<AppState>,
+    req_revision: NewProductRevision,
+) -> Result<RevisionInput, (StatusCode, Json<serde_json::Value>)> {
+    let name = req_revision.name.trim().to_string();
+    if name.is_empty() {
+        return Err(invalid_product("Product name is required".to_string()));
     }
 
-    let new_attribute = attribute::ActiveModel {
-        id: ActiveValue::NotSet,
-        kind: ActiveValue::set(req_attribute.kind),
-        label: ActiveValue::set(req_attribute.label),
-    };
+    let price = Decimal::from_f32(req_revision.price)
+        .filter(|price| price.is_sign_positive() && !price.is_zero())
+        .ok_or_else(|| invalid_product("Product price must be positive".to_string()))?;
 
-    let attribute = Attribute::insert(new_attribute)
-        .exec_with_returning(&data.db)
+    let images = parse_product_images(req_revision.images)?;
+
+    let mut attribute_ids: Vec<i32> = vec![];
+    for attribute in &req_revision.attributes {
+        if !attribute_ids.contains(&attribute.attribute_id) {
+            attribute_ids.push(attribute.attribute_id);
+        }
+    }
+
+    let attribute_count = Attribute::find()
+        .filter(attribute::Column::Id.is_in(attribute_ids.to_owned()))
+        .count(&data.db)
         .await
         .map_err(|e| {
             let error_response = serde_json::json!({
@@ -193,49 +694,81 @@ pub async fn create_attribute(
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
 
-    let mut attribute_options = vec![];
+    if attribute_count as usize != attribute_ids.len() {
+        return Err(invalid_product(
+            "One or more attributes could not be found".to_string(),
+        ));
+    }
 
-    for attribute_option in req_attribute.options {
-        let new_attribute_option = attribute_option::ActiveModel {
-            id: ActiveValue::NotSet,
-            attribute_id: ActiveValue::Set(attribute.id),
-            label: ActiveValue::Set(attribute_option.label),
-            content: ActiveValue::Set(attribute_option.content),
-        };
+    Ok(RevisionInput {
+        name,
+        description: req_revision.description,
+        price,
+        upc: req_revision.upc,
+        real_weight: req_revision.real_weight,
+        ship_weight: req_revision.ship_weight,
+        attributes: req_revision
+            .attributes
+            .into_iter()
+            .map(|attribute| (attribute.attribute_id, attribute.content))
+            .collect(),
+        images,
+    })
+}
 
-        let attribute_option = AttributeOption::insert(new_attribute_option)
-            .exec_with_returning(&data.db)
-            .await
-            .map_err(|e| {
-                let error_response = serde_json::json!({
-                    "status": "fail",
-                    "message": format!("Database error: {}", e),
-                });
-                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-            })?;
+async fn validate_product(
+    data: &Arc<AppState>,
+    req_product: NewProduct,
+    product_id: Option<i32>,
+) -> Result<ProductInput, (StatusCode, Json<serde_json::Value>)> {
+    let short_url = req_product.short_url.trim().to_ascii_lowercase();
+    if short_url.is_empty()
+        || !short_url
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(invalid_product(
+            "Product short url may only contain letters, numbers, dashes and underscores"
+                .to_string(),
+        ));
+    }
+    if short_url.parse::<i32>().is_ok() {
+        return Err(invalid_product(
+            "Product short url cannot be a number".to_string(),
+        ));
+    }
 
-        attribute_options.push(attribute_option);
+    if let Some(status) = &req_product.status {
+        if !PRODUCT_STATUSES.contains(&status.as_str()) {
+            return Err(invalid_product(format!(
+                "Product status must be one of: {}",
+                PRODUCT_STATUSES.join(", ")
+            )));
+        }
     }
 
-    let attribute_response = serde_json::json!({"status": "success","data": serde_json::json!({
-        "attribute": filter_attribute_record(&attribute, attribute_options)
-    })});
+    let publish_date = req_product.publish_date.map(|date| date.naive_utc());
+    let unpublish_date = req_product.unpublish_date.map(|date| date.naive_utc());
+    if let (Some(publish_date), Some(unpublish_date)) = (publish_date, unpublish_date) {
+        if unpublish_date <= publish_date {
+            return Err(invalid_product(
+                "Product unpublish date must be after its publish date".to_string(),
+            ));
+        }
+    }
 
-    Ok(Json(attribute_response))
-}
+    if req_product.stock < 0 {
+        return Err(invalid_product(
+            "Product stock cannot be negative".to_string(),
+        ));
+    }
 
-pub async fn update_attribute(
-    State(data): State<Arc<AppState>>,
-    Path(attribute_id): Path<String>,
-    Extension(user): Extension<account::Model>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
-}
+    let mut existing_product = Product::find().filter(product::Column::ShortUrl.eq(&short_url));
+    if let Some(product_id) = product_id {
+        existing_product = existing_product.filter(product::Column::Id.ne(product_id));
+    }
 
-pub async fn list_categories(
-    State(data): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    let all_categories = Category::find().all(&data.db).await.map_err(|e| {
+    let existing_product = existing_product.one(&data.db).await.map_err(|e| {
         let error_response = serde_json::json!({
             "status": "fail",
             "message": format!("Database error: {}", e),
@@ -243,36 +776,68 @@ pub async fn list_categories(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    let categories_response = serde_json::json!({"categories": all_categories.iter().map(|category| filter_category_record(category)).collect::<Vec<_>>()});
+    if existing_product.is_some() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product with that short url already exists",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
 
-    Ok(Json(categories_response))
-}
+    let mut category_ids: Vec<i32> = vec![];
+    for category_id in req_product.categories {
+        if !category_ids.contains(&(category_id as i32)) {
+            category_ids.push(category_id as i32);
+        }
+    }
 
-pub async fn retrieve_category(
-    State(data): State<Arc<AppState>>,
-    Path(category_id): Path<String>,
-    Extension(user): Extension<account::Model>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
+    let category_count = Category::find()
+        .filter(category::Column::Id.is_in(category_ids.to_owned()))
+        .count(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if category_count as usize != category_ids.len() {
+        return Err(invalid_product(
+            "One or more categories could not be found".to_string(),
+        ));
+    }
+
+    if let Some(primary_category_id) = req_product.primary_category_id {
+        if !category_ids.contains(&primary_category_id) {
+            return Err(invalid_product(
+                "The primary category must be one of the product's categories".to_string(),
+            ));
+        }
+    }
+
+    Ok(ProductInput {
+        short_url,
+        status: req_product.status,
+        publish_date,
+        unpublish_date,
+        category_ids,
+        primary_category_id: req_product.primary_category_id,
+        allow_back_order: req_product.allow_back_order.unwrap_or(false),
+        allow_restock_notifications: req_product.allow_restock_notifications.unwrap_or(true),
+        revision: validate_product_revision(data, req_product.revision).await?,
+    })
 }
 
-pub async fn create_category(
-    Extension(user): Extension<account::Model>,
+pub async fn create_product(
     State(data): State<Arc<AppState>>,
-    Json(req_category): Json<NewCategory>,
+    Json(req_product): Json<NewProduct>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if let Err(error) = check_admin(&user) {
-        return Err(error);
-    }
-
-    let new_category = category::ActiveModel {
-        id: ActiveValue::NotSet,
-        label: ActiveValue::set(req_category.label),
-        parent_id: ActiveValue::set(req_category.parent_id),
-    };
+    let initial_stock = req_product.stock;
+    let new_product = validate_product(&data, req_product, None).await?;
 
-    let category = Category::insert(new_category)
-        .exec_with_returning(&data.db)
+    let product = Mutation::create_product(&data.db, new_product, initial_stock)
         .await
         .map_err(|e| {
             let error_response = serde_json::json!({
@@ -282,17 +847,829 @@ pub async fn create_category(
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
 
-    let category_response = serde_json::json!({"status": "success","data": serde_json::json!({
-        "category": filter_category_record(&category)
+    let product_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "product": find_filtered_product(&data, product).await?
     })});
 
-    Ok(Json(category_response))
+    Ok((StatusCode::CREATED, Json(product_response)))
 }
 
-pub async fn update_category(
+pub async fn update_product(
     State(data): State<Arc<AppState>>,
-    Path(category_id): Path<String>,
-    Extension(user): Extension<account::Model>,
+    Path(product_id): Path<String>,
+    Json(req_product): Json<NewProduct>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    Ok(())
+    let product = find_product(&data, &product_id).await?;
+    let updated_product = validate_product(&data, req_product, Some(product.id)).await?;
+
+    let product = Mutation::update_product_by_id(&data.db, product.id, updated_product)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let product_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "product": find_filtered_product(&data, product).await?
+    })});
+
+    Ok(Json(product_response))
+}
+
+pub async fn delete_product(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    let has_orders = CoreQuery::find_product_has_orders(&data.db, &product)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if has_orders {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product has been ordered and cannot be deleted",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    Mutation::delete_product(&data.db, product.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status":  "success",
+        "data": {
+            "message": "Product has been deleted",
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+fn invalid_upload(e: MultipartError) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "fail",
+        "message": format!("Invalid upload: {}", e.body_text()),
+    });
+    (e.status(), Json(error_response))
+}
+
+/// Stores each image of a multipart upload, reporting the files that were refused.
+/// Fails only when nothing could be stored.
+pub async fn upload_product_image(
+    Extension(user): Extension<account::Model>,
+    State(data): State<Arc<AppState>>,
+    mut files: Multipart,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mut file_locations: HashSet<String> = HashSet::new();
+    let mut uploaded_images = vec![];
+    let mut rejected_files = vec![];
+    // Set when a file was turned away by storage or the database rather than for its content
+    let mut failed = false;
+
+    while let Some(file) = files.next_field().await.map_err(invalid_upload)? {
+        let file_name = file
+            .file_name()
+            .or(file.name())
+            .unwrap_or_default()
+            .to_string();
+        let content_type = file.content_type().map(str::to_string);
+        let bytes = file.bytes().await.map_err(invalid_upload)?;
+        let size = bytes.len();
+
+        // Decoding and encoding are CPU bound, so they are kept off the async workers
+        let sizes = data.env.image_variants.to_owned();
+        let declared_type = content_type.to_owned();
+        let converted = tokio::task::spawn_blocking(move || {
+            let format = validate_image(declared_type.as_deref(), &bytes)?;
+            convert_image_to_webp(&bytes, format, &sizes)
+        })
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Image processing error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+        let image = match converted {
+            Ok(image) => image,
+            Err(message) => {
+                rejected_files.push(serde_json::json!({
+                    "file": file_name,
+                    "message": message,
+                }));
+                continue;
+            }
+        };
+
+        let (width, height) = (image.original.width as i32, image.original.height as i32);
+        let (hash_id, variants) = match upload_image(data.storage.as_ref(), image).await {
+            Ok(uploaded) => uploaded,
+            Err((_, Json(error_response))) => {
+                failed = true;
+                rejected_files.push(serde_json::json!({
+                    "file": file_name,
+                    "message": error_response["message"],
+                }));
+                continue;
+            }
+        };
+        let stored_keys: Vec<String> = std::iter::once(hash_id.to_string())
+            .chain(variants.iter().map(|variant| variant.key.to_owned()))
+            .collect();
+        let media_input = MediaInput {
+            hash_id,
+            account_id: Some(user.id),
+            file_name: file_name.to_owned(),
+            content_type: content_type.unwrap_or_default(),
+            width,
+            height,
+            size: size as i64,
+            variants,
+        };
+        let (media, variants) = match Mutation::create_media(&data.db, media_input).await {
+            Ok(created) => created,
+            Err(e) => {
+                // Without a library entry nothing would ever clean up the stored files
+                for key in &stored_keys {
+                    let _ = delete_image(data.storage.as_ref(), key).await;
+                }
+                failed = true;
+                rejected_files.push(serde_json::json!({
+                    "file": file_name,
+                    "message": format!("Database error: {}", e),
+                }));
+                continue;
+            }
+        };
+
+        file_locations.insert(hash_id.to_string());
+        uploaded_images.push(filter_media_record(
+            data.storage.as_ref(),
+            &MediaDetails {
+                media,
+                uploader: Some(user.to_owned()),
+                variants,
+                usage: 0,
+            },
+        ));
+    }
+
+    if uploaded_images.is_empty() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": match rejected_files.is_empty() {
+                true => "No files were uploaded",
+                false => "None of the files could be uploaded",
+            },
+            "errors": rejected_files,
+        });
+        let status = match failed {
+            true => StatusCode::INTERNAL_SERVER_ERROR,
+            false => StatusCode::BAD_REQUEST,
+        };
+        return Err((status, Json(error_response)));
+    }
+
+    let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "files": file_locations,
+        "images": uploaded_images,
+        "errors": rejected_files,
+    })});
+
+    Ok(Json(user_response))
+}
+
+#[derive(Deserialize)]
+pub struct MediaRetrievalParams {
+    page: Option<u64>,
+    per_page: Option<u64>,
+    /// Part of the original file name
+    search: Option<String>,
+}
+
+/// The media library, newest uploads first
+pub async fn list_uploaded_images(
+    State(data): State<Arc<AppState>>,
+    Query(params): Query<MediaRetrievalParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params
+        .per_page
+        .unwrap_or(DEFAULT_MEDIA_PER_PAGE)
+        .clamp(1, MAX_MEDIA_PER_PAGE);
+    let search = params
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty());
+
+    let media_page = CoreQuery::find_media_in_page(&data.db, page, per_page, search)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let media_response = serde_json::json!({
+        "status": "success",
+        "results": media_page.media.len(),
+        "images": media_page.media.iter().map(|media| filter_media_record(data.storage.as_ref(), media)).collect::<Vec<_>>(),
+        "page": page,
+        "perPage": per_page,
+        "totalItems": media_page.total_items,
+        "totalPages": media_page.total_pages,
+    });
+
+    Ok(Json(media_response))
+}
+
+pub async fn list_attributes(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let all_attributes = CoreQuery::find_attributes(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attributes_response = serde_json::json!({"attributes": all_attributes.iter().map(|attribute| filter_attribute_record(&attribute.0, attribute.1.to_owned())).collect::<Vec<_>>()});
+
+    Ok(Json(attributes_response))
+}
+
+async fn find_attribute(
+    data: &Arc<AppState>,
+    attribute_id: i32,
+) -> Result<(attribute::Model, Vec<attribute_option::Model>), (StatusCode, Json<serde_json::Value>)>
+{
+    CoreQuery::find_attribute(&data.db, attribute_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Attribute not found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+fn invalid_attribute(message: String) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "fail",
+        "message": message,
+    });
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
+
+/// Checks the label and kind of an attribute, and every option's content against the kind.
+/// `existing_options` are the options an edited attribute already has.
+fn validate_attribute(
+    req_attribute: NewAttribute,
+    existing_options: &[attribute_option::Model],
+) -> Result<AttributeInput, (StatusCode, Json<serde_json::Value>)> {
+    let label = req_attribute.label.trim().to_string();
+    if label.is_empty() {
+        return Err(invalid_attribute(
+            "An attribute label is required".to_string(),
+        ));
+    }
+
+    if !ATTRIBUTE_KINDS.contains(&req_attribute.kind.as_str()) {
+        return Err(invalid_attribute(format!(
+            "Invalid attribute kind provided: {}, expected one of {}",
+            req_attribute.kind,
+            ATTRIBUTE_KINDS.join(", ")
+        )));
+    }
+
+    let mut labels = HashSet::new();
+    let mut option_ids = HashSet::new();
+    let mut options = vec![];
+    for option in req_attribute.options {
+        let option_label = option.label.trim().to_string();
+        if option_label.is_empty() {
+            return Err(invalid_attribute(
+                "Attribute options require a label".to_string(),
+            ));
+        }
+
+        if !labels.insert(option_label.to_lowercase()) {
+            return Err(invalid_attribute(format!(
+                "Duplicate attribute option: {}",
+                option_label
+            )));
+        }
+
+        if let Some(option_id) = option.id {
+            if !option_ids.insert(option_id)
+                || !existing_options
+                    .iter()
+                    .any(|existing| existing.id == option_id)
+            {
+                return Err(invalid_attribute(format!(
+                    "Attribute option {} does not belong to this attribute",
+                    option_id
+                )));
+            }
+        }
+
+        let content = option.content.trim().to_string();
+        validate_attribute_content(&req_attribute.kind, &content).map_err(|message| {
+            invalid_attribute(format!("Invalid option {}: {}", option_label, message))
+        })?;
+
+        options.push(AttributeOptionInput {
+            id: option.id,
+            label: option_label,
+            content,
+        });
+    }
+
+    Ok(AttributeInput {
+        label,
+        kind: req_attribute.kind,
+        options,
+    })
+}
+
+pub async fn retrieve_attribute(
+    State(data): State<Arc<AppState>>,
+    Path(attribute_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let (attribute, attribute_options) = find_attribute(&data, attribute_id).await?;
+
+    let attribute_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "attribute": filter_attribute_record(&attribute, attribute_options)
+    })});
+
+    Ok(Json(attribute_response))
+}
+
+pub async fn create_attribute(
+    State(data): State<Arc<AppState>>,
+    Json(req_attribute): Json<NewAttribute>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let attribute_input = validate_attribute(req_attribute, &[])?;
+
+    let (attribute, attribute_options) = Mutation::create_attribute(&data.db, attribute_input)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attribute_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "attribute": filter_attribute_record(&attribute, attribute_options)
+    })});
+
+    Ok(Json(attribute_response))
+}
+
+/// Replaces an attribute's label, kind and options. Options are kept by id, listed in
+/// their new order, and those left out are removed unless a variant still selects them.
+/// The kind only changes when every product value of the attribute fits the new one.
+pub async fn update_attribute(
+    State(data): State<Arc<AppState>>,
+    Path(attribute_id): Path<i32>,
+    Json(req_attribute): Json<NewAttribute>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let (attribute, existing_options) = find_attribute(&data, attribute_id).await?;
+    let attribute_input = validate_attribute(req_attribute, &existing_options)?;
+
+    if attribute_input.kind != attribute.kind {
+        let contents = CoreQuery::find_attribute_contents(&data.db, attribute.id)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        let invalid_contents: Vec<String> = contents
+            .into_iter()
+            .filter(|content| validate_attribute_content(&attribute_input.kind, content).is_err())
+            .collect();
+
+        if !invalid_contents.is_empty() {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!(
+                    "Product values do not fit the {} kind: {}",
+                    attribute_input.kind,
+                    invalid_contents.join(", ")
+                ),
+            });
+            return Err((StatusCode::CONFLICT, Json(error_response)));
+        }
+    }
+
+    let removed_option_ids: Vec<i32> = existing_options
+        .iter()
+        .filter(|existing| {
+            !attribute_input
+                .options
+                .iter()
+                .any(|option| option.id == Some(existing.id))
+        })
+        .map(|existing| existing.id)
+        .collect();
+
+    if !removed_option_ids.is_empty() {
+        let usage = CoreQuery::find_attribute_option_usage(&data.db, removed_option_ids)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        if usage > 0 {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Removed options are selected by {} product variants", usage),
+            });
+            return Err((StatusCode::CONFLICT, Json(error_response)));
+        }
+    }
+
+    let (attribute, attribute_options) =
+        Mutation::update_attribute(&data.db, attribute, attribute_input)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+    let attribute_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "attribute": filter_attribute_record(&attribute, attribute_options)
+    })});
+
+    Ok(Json(attribute_response))
+}
+
+pub async fn delete_attribute(
+    State(data): State<Arc<AppState>>,
+    Path(attribute_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let (attribute, _) = find_attribute(&data, attribute_id).await?;
+
+    let (product_attributes, variant_options) =
+        CoreQuery::find_attribute_usage(&data.db, attribute.id)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+    if product_attributes > 0 || variant_options > 0 {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!(
+                "Attribute is used by {} product revisions and {} variant options",
+                product_attributes, variant_options
+            ),
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    Mutation::delete_attribute(&data.db, attribute)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
+
+pub async fn list_categories(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let category_tree = CoreQuery::find_category_tree(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let categories_response = serde_json::json!({"categories": category_tree.iter().map(filter_category_node_record).collect::<Vec<_>>()});
+
+    Ok(Json(categories_response))
+}
+
+async fn find_category(
+    data: &Arc<AppState>,
+    category_id: i32,
+) -> Result<category::Model, (StatusCode, Json<serde_json::Value>)> {
+    CoreQuery::find_category_by_id(&data.db, category_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Category not found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+/// Checks a category's label and that its parent exists outside the category's own subtree
+async fn validate_category(
+    data: &Arc<AppState>,
+    category_id: Option<i32>,
+    req_category: NewCategory,
+) -> Result<CategoryInput, (StatusCode, Json<serde_json::Value>)> {
+    let label = req_category.label.trim().to_string();
+    if label.is_empty() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "A category label is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    if let Some(parent_id) = req_category.parent_id {
+        find_category(data, parent_id)
+            .await
+            .map_err(|(status, error)| {
+                if status == StatusCode::NOT_FOUND {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": "Parent category not found",
+                    });
+                    (StatusCode::BAD_REQUEST, Json(error_response))
+                } else {
+                    (status, error)
+                }
+            })?;
+
+        if let Some(category_id) = category_id {
+            let subtree_ids = CoreQuery::find_category_tree_ids(&data.db, category_id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            if subtree_ids.contains(&parent_id) {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": "A category cannot be moved below itself or one of its descendants",
+                });
+                return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+            }
+        }
+    }
+
+    Ok(CategoryInput {
+        label,
+        parent_id: req_category.parent_id,
+        position: req_category.position,
+    })
+}
+
+pub async fn retrieve_category(
+    State(data): State<Arc<AppState>>,
+    Path(category_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let category = find_category(&data, category_id).await?;
+
+    let breadcrumbs = CoreQuery::find_category_breadcrumbs(&data.db, category.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let category_tree = CoreQuery::find_category_tree(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let mut nodes = category_tree.iter().collect::<Vec<_>>();
+    let mut children = vec![];
+    while let Some(node) = nodes.pop() {
+        if node.category.id == category.id {
+            children = node
+                .children
+                .iter()
+                .map(filter_category_node_record)
+                .collect();
+            break;
+        }
+        nodes.extend(node.children.iter());
+    }
+
+    let category_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "category": filter_category_record(&category),
+        "breadcrumbs": breadcrumbs.iter().map(filter_category_record).collect::<Vec<_>>(),
+        "children": children,
+    })});
+
+    Ok(Json(category_response))
+}
+
+pub async fn create_category(
+    State(data): State<Arc<AppState>>,
+    Json(req_category): Json<NewCategory>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let category_input = validate_category(&data, None, req_category).await?;
+
+    let category = Mutation::create_category(&data.db, category_input)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let category_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "category": filter_category_record(&category)
+    })});
+
+    Ok(Json(category_response))
+}
+
+pub async fn update_category(
+    State(data): State<Arc<AppState>>,
+    Path(category_id): Path<i32>,
+    Json(req_category): Json<NewCategory>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let category = find_category(&data, category_id).await?;
+    let category_input = validate_category(&data, Some(category.id), req_category).await?;
+
+    let category = Mutation::update_category(&data.db, category, category_input)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let category_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "category": filter_category_record(&category)
+    })});
+
+    Ok(Json(category_response))
+}
+
+#[derive(Deserialize)]
+pub struct CategoryDeletionParams {
+    reassign_to: Option<i32>,
+}
+
+/// Deletes a category, moving its children and products to `reassign_to`. Without a
+/// target the deletion is rejected unless the category is empty.
+pub async fn delete_category(
+    State(data): State<Arc<AppState>>,
+    Path(category_id): Path<i32>,
+    Query(params): Query<CategoryDeletionParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let category = find_category(&data, category_id).await?;
+
+    match params.reassign_to {
+        Some(target_id) => {
+            find_category(&data, target_id)
+                .await
+                .map_err(|(status, error)| {
+                    if status == StatusCode::NOT_FOUND {
+                        let error_response = serde_json::json!({
+                            "status": "fail",
+                            "message": "Reassignment category not found",
+                        });
+                        (StatusCode::BAD_REQUEST, Json(error_response))
+                    } else {
+                        (status, error)
+                    }
+                })?;
+
+            let subtree_ids = CoreQuery::find_category_tree_ids(&data.db, category.id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            if subtree_ids.contains(&target_id) {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": "Cannot reassign to the deleted category or one of its descendants",
+                });
+                return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+            }
+        }
+        None => {
+            let child_count = CoreQuery::find_category_child_count(&data.db, category.id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+            let product_count = CoreQuery::find_category_product_count(&data.db, category.id)
+                .await
+                .map_err(|e| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", e),
+                    });
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            if child_count > 0 || product_count > 0 {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!(
+                        "Category has {} subcategories and {} products, provide a category to reassign them to",
+                        child_count, product_count
+                    ),
+                });
+                return Err((StatusCode::CONFLICT, Json(error_response)));
+            }
+        }
+    }
+
+    Mutation::delete_category(&data.db, category, params.reassign_to)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(Json(serde_json::json!({"status": "success"})))
 }
@@ -0,0 +1,289 @@
+use crate::{
+    request::{NewProductRevision, NewRevisionSchedule},
+    response::{FilteredProductEvent, FilteredProductRevision},
+    routes::product::{find_filtered_product, find_product, validate_product_revision},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use entity::*;
+use rust_ecom_service_core::{
+    catalog::{ProductRevision, REVISION_STATUS_DRAFT, REVISION_STATUS_SCHEDULED},
+    AppState, Mutation, Query as CoreQuery,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+fn filter_revision_record(
+    product: &product::Model,
+    revision: &product_detail::Model,
+) -> FilteredProductRevision {
+    FilteredProductRevision {
+        id: revision.id,
+        parent_id: revision.parent_id,
+        name: revision.name.to_owned(),
+        price: revision.price,
+        status: revision.status.to_owned(),
+        creation_date: revision.creation_date,
+        scheduled_date: revision.scheduled_date,
+        live: revision.id == product.revision_id,
+    }
+}
+
+async fn find_revision(
+    data: &Arc<AppState>,
+    product: &product::Model,
+    revision_id: i32,
+) -> Result<ProductRevision, (StatusCode, Json<serde_json::Value>)> {
+    CoreQuery::find_product_revision(&data.db, product.id, revision_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product revision could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+pub async fn list_revisions(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    let revisions = CoreQuery::find_product_revisions(&data.db, product.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "results": revisions.len(),
+        "revisions": revisions
+            .iter()
+            .map(|revision| filter_revision_record(&product, revision))
+            .collect::<Vec<FilteredProductRevision>>(),
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn create_draft(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Json(req_revision): Json<NewProductRevision>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let new_revision = validate_product_revision(&data, req_revision).await?;
+
+    let draft = Mutation::create_product_draft(&data.db, &product, new_revision)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "revision": filter_revision_record(&product, &draft)
+    })});
+
+    Ok((StatusCode::CREATED, Json(json_response)))
+}
+
+pub async fn publish_draft(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, revision_id)): Path<(String, i32)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let draft = find_revision(&data, &product, revision_id).await?;
+
+    if draft.detail.status != REVISION_STATUS_DRAFT
+        && draft.detail.status != REVISION_STATUS_SCHEDULED
+    {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Only draft or scheduled revisions can be published",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let product = Mutation::publish_product_draft(&data.db, product, draft.detail)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let product_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "product": find_filtered_product(&data, product).await?
+    })});
+
+    Ok(Json(product_response))
+}
+
+pub async fn schedule_draft(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, revision_id)): Path<(String, i32)>,
+    Json(req_schedule): Json<NewRevisionSchedule>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let draft = find_revision(&data, &product, revision_id).await?;
+
+    if draft.detail.status != REVISION_STATUS_DRAFT
+        && draft.detail.status != REVISION_STATUS_SCHEDULED
+    {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Only draft or scheduled revisions can be scheduled",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let publish_date = req_schedule.publish_date.naive_utc();
+    if publish_date <= Utc::now().naive_utc() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Publish date must be in the future",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let draft = Mutation::schedule_product_draft(&data.db, draft.detail, publish_date)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "revision": filter_revision_record(&product, &draft)
+    })});
+
+    Ok(Json(json_response))
+}
+
+pub async fn list_product_events(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    let events = CoreQuery::find_product_events(&data.db, product.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "results": events.len(),
+        "events": events
+            .iter()
+            .map(|event| FilteredProductEvent {
+                id: event.id,
+                revision_id: event.revision_id,
+                kind: event.kind.to_owned(),
+                creation_date: event.creation_date,
+            })
+            .collect::<Vec<FilteredProductEvent>>(),
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn rollback_revision(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, revision_id)): Path<(String, i32)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let revision = find_revision(&data, &product, revision_id).await?;
+
+    if revision.detail.status == REVISION_STATUS_DRAFT
+        || revision.detail.status == REVISION_STATUS_SCHEDULED
+    {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Drafts must be published rather than rolled back to",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    if revision.detail.id == product.revision_id {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product revision is already live",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let product = Mutation::rollback_product(&data.db, product, revision.detail.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let product_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "product": find_filtered_product(&data, product).await?
+    })});
+
+    Ok(Json(product_response))
+}
+
+#[derive(Deserialize)]
+pub struct RevisionDiffParams {
+    from: i32,
+    to: i32,
+}
+
+pub async fn diff_revisions(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+    Query(params): Query<RevisionDiffParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+    let from = find_revision(&data, &product, params.from).await?;
+    let to = find_revision(&data, &product, params.to).await?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "from": filter_revision_record(&product, &from.detail),
+        "to": filter_revision_record(&product, &to.detail),
+        "changes": from.diff(&to),
+    });
+
+    Ok(Json(json_response))
+}
@@ -0,0 +1,367 @@
+use crate::{
+    request::{AccountRoles, NewRole, UpdatedRole},
+    response::{FilteredRole, FilteredSimpleRole},
+    routes::account::find_account,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use entity::role;
+use rust_ecom_service_core::{
+    permission::{Permission, RoleDetails, RoleInput, ADMIN_ROLE_NAME},
+    AppState, Mutation, Query,
+};
+use std::sync::Arc;
+
+fn filter_role_record(details: &RoleDetails) -> FilteredRole {
+    FilteredRole {
+        id: details.role.id,
+        name: details.role.name.to_owned(),
+        description: details.role.description.to_owned(),
+        permissions: details
+            .permissions
+            .iter()
+            .map(|permission| permission.as_str())
+            .collect(),
+    }
+}
+
+fn filter_simple_role_record(role: &role::Model) -> FilteredSimpleRole {
+    FilteredSimpleRole {
+        id: role.id,
+        name: role.name.to_owned(),
+        description: role.description.to_owned(),
+    }
+}
+
+fn invalid_role(message: String) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "fail",
+        "message": message,
+    });
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
+
+fn parse_permissions(
+    permissions: Vec<String>,
+) -> Result<Vec<Permission>, (StatusCode, Json<serde_json::Value>)> {
+    let mut parsed: Vec<Permission> = vec![];
+    for permission in permissions {
+        let permission = Permission::parse(&permission)
+            .ok_or_else(|| invalid_role(format!("Unknown permission: {}", permission)))?;
+        if !parsed.contains(&permission) {
+            parsed.push(permission);
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// An empty description clears it
+fn role_description(description: String) -> Option<String> {
+    Some(description.trim().to_owned()).filter(|description| !description.is_empty())
+}
+
+/// Checks the name of a role is given and not taken by another role
+async fn validate_role_name(
+    data: &Arc<AppState>,
+    role_id: Option<i32>,
+    name: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if name.trim().is_empty() {
+        return Err(invalid_role("Role name must not be empty".to_string()));
+    }
+
+    let existing = Query::find_role_by_name(&data.db, name)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if existing.is_some_and(|existing| Some(existing.id) != role_id) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Role with that name already exists",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    Ok(())
+}
+
+async fn find_role(
+    data: &Arc<AppState>,
+    role_id: i32,
+) -> Result<RoleDetails, (StatusCode, Json<serde_json::Value>)> {
+    Query::find_role(&data.db, role_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Role not found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+/// Finds a role that may be edited, which the admin role never may
+async fn find_editable_role(
+    data: &Arc<AppState>,
+    role_id: i32,
+) -> Result<RoleDetails, (StatusCode, Json<serde_json::Value>)> {
+    let details = find_role(data, role_id).await?;
+
+    if details.role.name == ADMIN_ROLE_NAME {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "The admin role cannot be changed",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    Ok(details)
+}
+
+async fn account_roles_response(
+    data: &Arc<AppState>,
+    account_id: i32,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let roles = Query::find_account_roles(&data.db, account_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "roles": roles
+                .iter()
+                .map(filter_simple_role_record)
+                .collect::<Vec<FilteredSimpleRole>>(),
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn list_permissions() -> impl IntoResponse {
+    Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "permissions": Permission::ALL
+                .iter()
+                .map(|permission| permission.as_str())
+                .collect::<Vec<_>>(),
+        }
+    }))
+}
+
+pub async fn list_roles(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let roles = Query::find_roles(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "data": {
+            "roles": roles.iter().map(filter_role_record).collect::<Vec<FilteredRole>>(),
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn create_role(
+    State(data): State<Arc<AppState>>,
+    Json(req_role): Json<NewRole>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let name = req_role.name.trim().to_owned();
+    validate_role_name(&data, None, &name).await?;
+    let permissions = parse_permissions(req_role.permissions)?;
+
+    let role = Mutation::create_role(
+        &data.db,
+        RoleInput {
+            name,
+            description: req_role.description.and_then(role_description),
+            permissions,
+        },
+    )
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let role_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "role": filter_role_record(&role)
+    })});
+
+    Ok(Json(role_response))
+}
+
+pub async fn update_role(
+    State(data): State<Arc<AppState>>,
+    Path(role_id): Path<i32>,
+    Json(req_role): Json<UpdatedRole>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let existing = find_editable_role(&data, role_id).await?;
+
+    let name = match req_role.name {
+        Some(name) => {
+            let name = name.trim().to_owned();
+            validate_role_name(&data, Some(role_id), &name).await?;
+            name
+        }
+        None => existing.role.name,
+    };
+    let permissions = match req_role.permissions {
+        Some(permissions) => parse_permissions(permissions)?,
+        None => existing.permissions,
+    };
+
+    let role = Mutation::update_role(
+        &data.db,
+        role_id,
+        RoleInput {
+            name,
+            description: match req_role.description {
+                Some(description) => role_description(description),
+                None => existing.role.description,
+            },
+            permissions,
+        },
+    )
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let role_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "role": filter_role_record(&role)
+    })});
+
+    Ok(Json(role_response))
+}
+
+pub async fn delete_role(
+    State(data): State<Arc<AppState>>,
+    Path(role_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let role = find_editable_role(&data, role_id).await?;
+
+    Mutation::delete_role(&data.db, role.role.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
+
+pub async fn list_account_roles(
+    State(data): State<Arc<AppState>>,
+    Path(account_id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let account = find_account(&data, account_id).await?;
+
+    account_roles_response(&data, account.id).await
+}
+
+/// Replaces the roles of an account, refusing to take the admin role from its last holder
+pub async fn update_account_roles(
+    State(data): State<Arc<AppState>>,
+    Path(account_id): Path<i32>,
+    Json(req_roles): Json<AccountRoles>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let account = find_account(&data, account_id).await?;
+
+    let roles = Query::find_roles(&data.db).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let mut role_ids: Vec<i32> = vec![];
+    for role_id in req_roles.role_ids {
+        if !roles.iter().any(|details| details.role.id == role_id) {
+            return Err(invalid_role(format!("Role not found: {}", role_id)));
+        }
+        if !role_ids.contains(&role_id) {
+            role_ids.push(role_id);
+        }
+    }
+
+    if let Some(admin) = roles
+        .iter()
+        .find(|details| details.role.name == ADMIN_ROLE_NAME)
+    {
+        let holders = Query::find_role_account_ids(&data.db, admin.role.id)
+            .await
+            .map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        if !role_ids.contains(&admin.role.id) && holders == [account.id] {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "The last account with the admin role must keep it",
+            });
+            return Err((StatusCode::CONFLICT, Json(error_response)));
+        }
+    }
+
+    Mutation::set_account_roles(&data.db, account.id, role_ids)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    account_roles_response(&data, account.id).await
+}
@@ -0,0 +1,239 @@
+use crate::{
+    request::UpdatedProductVariant,
+    response::FilteredProductVariant,
+    routes::product::{filter_product_variant_record, find_product},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use entity::{prelude::*, *};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use rust_ecom_service_core::{
+    catalog::{is_product_visible, VariantDetails, VariantInput},
+    sea_orm::{prelude::Uuid, ColumnTrait, EntityTrait, QueryFilter},
+    AppState, Mutation, Query as CoreQuery,
+};
+use std::sync::Arc;
+
+async fn find_variant_records(
+    data: &Arc<AppState>,
+    product: &product::Model,
+) -> Result<Vec<VariantDetails>, (StatusCode, Json<serde_json::Value>)> {
+    CoreQuery::find_product_variants(&data.db, product.id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })
+}
+
+async fn find_base_price(
+    data: &Arc<AppState>,
+    product: &product::Model,
+) -> Result<Decimal, (StatusCode, Json<serde_json::Value>)> {
+    ProductDetail::find_by_id(product.revision_id)
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .map(|detail| detail.price)
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product revision could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+pub async fn list_variants(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    if !is_product_visible(&product, Utc::now().naive_utc()) {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Product could not be found",
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    }
+
+    let base_price = find_base_price(&data, &product).await?;
+    let variants: Vec<_> = find_variant_records(&data, &product)
+        .await?
+        .into_iter()
+        .filter(|variant| variant.variant.active)
+        .collect();
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "results": variants.len(),
+        "variants": variants
+            .iter()
+            .map(|variant| filter_product_variant_record(data.storage.as_ref(), variant, base_price))
+            .collect::<Vec<FilteredProductVariant>>(),
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn generate_variants(
+    State(data): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    let created = Mutation::generate_product_variants(&data.db, &product)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let base_price = find_base_price(&data, &product).await?;
+    let variants = find_variant_records(&data, &product).await?;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "created": created.len(),
+        "variants": variants
+            .iter()
+            .map(|variant| filter_product_variant_record(data.storage.as_ref(), variant, base_price))
+            .collect::<Vec<FilteredProductVariant>>(),
+    });
+
+    Ok((StatusCode::CREATED, Json(json_response)))
+}
+
+pub async fn update_variant(
+    State(data): State<Arc<AppState>>,
+    Path((product_id, variant_id)): Path<(String, i32)>,
+    Json(req_variant): Json<UpdatedProductVariant>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let product = find_product(&data, &product_id).await?;
+
+    let variant = CoreQuery::find_product_variant(&data.db, product.id, variant_id)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .ok_or_else(|| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": "Product variant could not be found",
+            });
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })?;
+
+    let sku = req_variant.sku.trim().to_string();
+    if sku.is_empty() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Variant SKU is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let existing_variant = ProductVariant::find()
+        .filter(product_variant::Column::Sku.eq(&sku))
+        .filter(product_variant::Column::Id.ne(variant.id))
+        .one(&data.db)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "fail",
+                "message": format!("Database error: {}", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if existing_variant.is_some() {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": "Variant with that SKU already exists",
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let price = match req_variant.price {
+        Some(price) => Some(
+            Decimal::from_f32(price)
+                .filter(|price| price.is_sign_positive() && !price.is_zero())
+                .ok_or_else(|| {
+                    let error_response = serde_json::json!({
+                        "status": "fail",
+                        "message": "Variant price must be positive",
+                    });
+                    (StatusCode::BAD_REQUEST, Json(error_response))
+                })?,
+        ),
+        None => None,
+    };
+
+    let image_ids = req_variant
+        .images
+        .iter()
+        .map(|image_id| {
+            Uuid::parse_str(image_id).map_err(|_| {
+                let error_response = serde_json::json!({
+                    "status": "fail",
+                    "message": format!("Invalid image id provided: {}", image_id),
+                });
+                (StatusCode::BAD_REQUEST, Json(error_response))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Mutation::update_product_variant(
+        &data.db,
+        variant,
+        VariantInput {
+            sku,
+            price,
+            active: req_variant.active.unwrap_or(true),
+            image_ids,
+        },
+    )
+    .await
+    .map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Database error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let base_price = find_base_price(&data, &product).await?;
+    let variant = find_variant_records(&data, &product)
+        .await?
+        .into_iter()
+        .find(|variant| variant.variant.id == variant_id)
+        .map(|variant| filter_product_variant_record(data.storage.as_ref(), &variant, base_price));
+
+    let json_response = serde_json::json!({"status": "success","data": serde_json::json!({
+        "variant": variant
+    })});
+
+    Ok(Json(json_response))
+}
@@ -1,73 +1,503 @@
+use async_trait::async_trait;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::Client;
 use axum::Json;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use http::StatusCode;
-use image::{imageops, DynamicImage, GenericImageView};
+use image::{
+    imageops,
+    io::{Limits, Reader},
+    DynamicImage, GenericImageView, ImageFormat,
+};
+use rust_ecom_service_core::{
+    catalog::ImageVariantInput,
+    storage::{StorageBackend, StorageError, StoredObject},
+    ImageVariantSize, StorageConfig,
+};
+use std::{
+    io::Cursor,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
 use uuid::Uuid;
 use webp::{Encoder, WebPMemory};
 
-const BUCKET_NAME: &str = "lemonseeds";
-
-pub fn convert_image_to_webp(bytes: &[u8]) -> WebPMemory {
-    let img = image::load_from_memory(bytes).unwrap();
-    let (w, h) = img.dimensions();
-    let size_factor = 1.0;
-    let img: DynamicImage = image::DynamicImage::ImageRgba8(imageops::resize(
-        &img,
-        (w as f64 * size_factor) as u32,
-        (h as f64 * size_factor) as u32,
-        imageops::FilterType::Triangle,
-    ));
-    let encoder: Encoder = Encoder::from_image(&img).unwrap();
-    let webp: WebPMemory = encoder.encode(50f32);
-    webp
-}
-
-pub async fn upload_image(image: Vec<u8>) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
-    let config = aws_config::load_from_env().await;
-    let client = Client::new(&config);
-    let file_key = Uuid::new_v4().to_string();
-    let image_bytestream = ByteStream::from(image);
-    client
-        .put_object()
-        .bucket(BUCKET_NAME)
-        .key(&file_key)
-        .body(image_bytestream)
-        .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
-        .send()
-        .await
-        .map_err(|e| {
-            let error_response = serde_json::json!({
-                "status": "fail",
-                "message": format!("Image upload error: {}", e),
-            });
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?;
-
-    Ok(file_key)
-}
-
-pub async fn get_uploaded_images() -> Result<Vec<String>, (StatusCode, Json<serde_json::Value>)> {
-    let config = aws_config::load_from_env().await;
-    let client = Client::new(&config);
-    let images = client
-        .list_objects_v2()
-        .bucket(BUCKET_NAME)
-        .send()
+/// Route the local backend's files are served from
+pub const LOCAL_STORAGE_ROUTE: &str = "/uploads";
+
+pub const IMAGE_CONTENT_TYPE: &str = "image/webp";
+
+const WEBP_QUALITY: f32 = 50.0;
+
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+
+/// Image types accepted for upload, by the content type they must be sent with
+const UPLOAD_IMAGE_FORMATS: [(&str, ImageFormat); 4] = [
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/png", ImageFormat::Png),
+    ("image/webp", ImageFormat::WebP),
+    ("image/gif", ImageFormat::Gif),
+];
+
+pub const MAX_UPLOAD_FILE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Allows for several files at the per file limit in one upload
+pub const MAX_UPLOAD_REQUEST_BYTES: usize = 5 * MAX_UPLOAD_FILE_BYTES;
+
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+const MAX_IMAGE_PIXELS: u64 = 40_000_000;
+
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+}
+
+impl S3Storage {
+    pub async fn new(bucket: String) -> S3Storage {
+        let config = aws_config::load_from_env().await;
+        S3Storage {
+            client: Client::new(&config),
+            bucket,
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .body(ByteStream::from(bytes))
+            .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
+            .send()
+            .await
+            .map_err(|e| StorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| StorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>, StorageError> {
+        let mut objects = vec![];
+        let mut continuation_token = None;
+
+        loop {
+            let page = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .map_err(|e| StorageError(e.to_string()))?;
+
+            objects.extend(page.contents().iter().filter_map(|object| {
+                Some(StoredObject {
+                    key: object.key()?.to_string(),
+                    size: object.size().unwrap_or_default() as u64,
+                    // An unknown age is treated as new so the file is never taken for stale
+                    last_modified: object
+                        .last_modified()
+                        .and_then(|date| NaiveDateTime::from_timestamp_opt(date.secs(), 0))
+                        .unwrap_or_else(|| Utc::now().naive_utc()),
+                })
+            }));
+
+            match page.next_continuation_token() {
+                Some(token) => continuation_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+
+        Ok(objects)
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("https://{}.s3.amazonaws.com/{}", self.bucket, key)
+    }
+}
+
+/// Keeps files in a directory on disk, for development and CI
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(path: String, public_url: String) -> LocalStorage {
+        LocalStorage {
+            root: PathBuf::from(path),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Resolves a key below the storage root, refusing keys that would escape it
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(StorageError(format!("Invalid storage key: {}", key)));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StorageError(e.to_string()))?;
+        }
+
+        tokio::fs::write(path, bytes)
+            .await
+            .map_err(|e| StorageError(e.to_string()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(StorageError(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>, StorageError> {
+        let mut objects = vec![];
+        let mut directories = vec![self.root.to_owned()];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = match tokio::fs::read_dir(&directory).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(StorageError(e.to_string())),
+            };
+
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| StorageError(e.to_string()))?
+            {
+                let path = entry.path();
+                let metadata = entry
+                    .metadata()
+                    .await
+                    .map_err(|e| StorageError(e.to_string()))?;
+                if metadata.is_dir() {
+                    directories.push(path);
+                } else if let Ok(key) = path.strip_prefix(&self.root) {
+                    objects.push(StoredObject {
+                        key: key.to_string_lossy().replace('\\', "/"),
+                        size: metadata.len(),
+                        last_modified: metadata
+                            .modified()
+                            .map(|modified| DateTime::<Utc>::from(modified).naive_utc())
+                            .unwrap_or_else(|_| Utc::now().naive_utc()),
+                    });
+                }
+            }
+        }
+
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
+
+pub async fn get_storage_backend(config: &StorageConfig) -> Arc<dyn StorageBackend> {
+    match config {
+        StorageConfig::S3 { bucket } => Arc::new(S3Storage::new(bucket.to_owned()).await),
+        StorageConfig::Local { path, public_url } => {
+            Arc::new(LocalStorage::new(path.to_owned(), public_url.to_owned()))
+        }
+    }
+}
+
+/// An upload re-encoded as WebP, carrying only pixel data
+pub struct EncodedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An upload at its original size together with its configured resizes
+pub struct ConvertedImage {
+    pub original: EncodedImage,
+    pub variants: Vec<(String, EncodedImage)>,
+}
+
+/// Reads the EXIF orientation tag of a JPEG, if it has one
+fn exif_orientation(bytes: &[u8]) -> Option<u16> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut offset = 2;
+    while bytes.get(offset) == Some(&0xFF) {
+        let marker = *bytes.get(offset + 1)?;
+        // Metadata segments all come before the image data starts
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+        let length =
+            u16::from_be_bytes([*bytes.get(offset + 2)?, *bytes.get(offset + 3)?]) as usize;
+        let segment = bytes.get(offset + 4..offset + 2 + length)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return tiff_orientation(&segment[6..]);
+        }
+        offset += 2 + length;
+    }
+
+    None
+}
+
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read_u16 = |at: usize| {
+        let bytes = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(match big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    };
+    let read_u32 = |at: usize| {
+        let bytes = [
+            *tiff.get(at)?,
+            *tiff.get(at + 1)?,
+            *tiff.get(at + 2)?,
+            *tiff.get(at + 3)?,
+        ];
+        Some(match big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    };
+
+    let directory = read_u32(4)? as usize;
+    let entries = read_u16(directory)? as usize;
+    (0..entries)
+        .map(|entry| directory + 2 + entry * 12)
+        .find(|&entry| read_u16(entry) == Some(EXIF_ORIENTATION_TAG))
+        .and_then(|entry| read_u16(entry + 8))
+}
+
+/// Turns the pixels upright, since the tag saying how is dropped with the rest of the EXIF
+fn apply_orientation(img: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+fn encode_webp(img: &DynamicImage) -> Result<EncodedImage, String> {
+    let img = DynamicImage::ImageRgba8(img.to_rgba8());
+    let encoder: Encoder =
+        Encoder::from_image(&img).map_err(|e| format!("Image could not be encoded: {}", e))?;
+    let webp: WebPMemory = encoder.encode(WEBP_QUALITY);
+    Ok(EncodedImage {
+        bytes: webp.to_vec(),
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
+fn image_limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits
+}
+
+/// Checks an uploaded file before it is decoded: its size, that the declared content type
+/// is an accepted image type matching the file's magic bytes, and that the dimensions in
+/// its header are within bounds, so oversized images are refused before allocating pixels
+pub fn validate_image(content_type: Option<&str>, bytes: &[u8]) -> Result<ImageFormat, String> {
+    if bytes.is_empty() {
+        return Err("File is empty".to_string());
+    }
+    if bytes.len() > MAX_UPLOAD_FILE_BYTES {
+        return Err(format!(
+            "File is larger than the {} MB limit",
+            MAX_UPLOAD_FILE_BYTES / 1024 / 1024
+        ));
+    }
+
+    let content_type = content_type.unwrap_or_default();
+    let format = UPLOAD_IMAGE_FORMATS
+        .iter()
+        .find(|(accepted, _)| *accepted == content_type)
+        .map(|(_, format)| *format)
+        .ok_or_else(|| format!("Unsupported content type: {}", content_type))?;
+    if image::guess_format(bytes).ok() != Some(format) {
+        return Err(format!(
+            "File contents are not a valid {} image",
+            content_type
+        ));
+    }
+
+    let mut reader = Reader::with_format(Cursor::new(bytes), format);
+    reader.limits(image_limits());
+    let (width, height) = reader
+        .into_dimensions()
+        .map_err(|e| format!("Image could not be read: {}", e))?;
+    if width as u64 * height as u64 > MAX_IMAGE_PIXELS {
+        return Err(format!(
+            "Image is {}x{}, more than the {} megapixel limit",
+            width,
+            height,
+            MAX_IMAGE_PIXELS / 1_000_000
+        ));
+    }
+
+    Ok(format)
+}
+
+/// Re-encodes an upload at its original size and at every variant size, keeping the
+/// aspect ratio and never enlarging it. Only the pixels are re-encoded, which strips EXIF.
+pub fn convert_image_to_webp(
+    bytes: &[u8],
+    format: ImageFormat,
+    sizes: &[ImageVariantSize],
+) -> Result<ConvertedImage, String> {
+    let mut reader = Reader::with_format(Cursor::new(bytes), format);
+    reader.limits(image_limits());
+    let img = reader
+        .decode()
+        .map_err(|e| format!("Image could not be decoded: {}", e))?;
+    let img = match exif_orientation(bytes) {
+        Some(orientation) => apply_orientation(img, orientation),
+        None => img,
+    };
+
+    let mut variants = vec![];
+    for size in sizes {
+        let (w, h) = img.dimensions();
+        let encoded = if w <= size.max_width && h <= size.max_height {
+            encode_webp(&img)?
+        } else {
+            encode_webp(&img.resize(
+                size.max_width,
+                size.max_height,
+                imageops::FilterType::Triangle,
+            ))?
+        };
+        variants.push((size.name.to_owned(), encoded));
+    }
+
+    Ok(ConvertedImage {
+        original: encode_webp(&img)?,
+        variants,
+    })
+}
+
+/// Where a variant is stored, next to the original kept under the image id
+pub fn image_variant_key(hash_id: &Uuid, name: &str) -> String {
+    format!("{}_{}", hash_id, name)
+}
+
+/// The image a stored file belongs to, when the key is an image id or one of its variants
+pub fn image_key_id(key: &str) -> Option<Uuid> {
+    let hash_id = key.get(..36)?;
+    let variant = &key[36..];
+    if !variant.is_empty() && !variant.starts_with('_') {
+        return None;
+    }
+    Uuid::parse_str(hash_id).ok()
+}
+
+/// Stores an upload and its variants, returning the new image id and the stored variants
+pub async fn upload_image(
+    storage: &dyn StorageBackend,
+    image: ConvertedImage,
+) -> Result<(Uuid, Vec<ImageVariantInput>), (StatusCode, Json<serde_json::Value>)> {
+    let hash_id = Uuid::new_v4();
+    let upload_error = |e: StorageError| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Image upload error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    };
+
+    storage
+        .put(
+            &hash_id.to_string(),
+            image.original.bytes,
+            IMAGE_CONTENT_TYPE,
+        )
         .await
-        .map_err(|e| {
-            let error_response = serde_json::json!({
-                "status": "fail",
-                "message": format!("Image listing error: {}", e),
-            });
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?;
-
-    let images = images
-        .contents()
-        .into_iter()
-        .map(|obj| String::from(obj.key().unwrap_or_default()))
-        .collect::<Vec<String>>();
-
-    Ok(images)
+        .map_err(upload_error)?;
+
+    let mut variants: Vec<ImageVariantInput> = vec![];
+    for (name, variant) in image.variants {
+        let key = image_variant_key(&hash_id, &name);
+        if let Err(e) = storage.put(&key, variant.bytes, IMAGE_CONTENT_TYPE).await {
+            // Remove what was already stored so a failed upload leaves nothing behind
+            for variant in &variants {
+                let _ = storage.delete(&variant.key).await;
+            }
+            let _ = storage.delete(&hash_id.to_string()).await;
+            return Err(upload_error(e));
+        }
+        variants.push(ImageVariantInput {
+            name,
+            key,
+            width: variant.width as i32,
+            height: variant.height as i32,
+        });
+    }
+
+    Ok((hash_id, variants))
+}
+
+pub async fn delete_image(
+    storage: &dyn StorageBackend,
+    file_key: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    storage.delete(file_key).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "fail",
+            "message": format!("Image deletion error: {}", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
 }
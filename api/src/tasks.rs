@@ -0,0 +1,198 @@
+use crate::{email::send_low_stock_alert_email, storage::image_key_id};
+use chrono::Utc;
+use entity::{prelude::*, *};
+use rust_ecom_service_core::{
+    inventory::is_low_stock,
+    sea_orm::{
+        prelude::Uuid, ActiveModelTrait, ActiveValue, ColumnTrait, DbErr, EntityTrait, QueryFilter,
+    },
+    storage::StoredObject,
+    AppState, Mutation, Query,
+};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+const LOW_STOCK_CHECK_INTERVAL: u64 = 300;
+const SCHEDULED_PUBLISH_INTERVAL: u64 = 60;
+const ORPHANED_IMAGE_CLEANUP_INTERVAL: u64 = 3600;
+const LOGIN_THROTTLE_CLEANUP_INTERVAL: u64 = 900;
+
+pub fn spawn_background_tasks(app_state: &Arc<AppState>) {
+    let data = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(LOW_STOCK_CHECK_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = check_low_stock(&data).await {
+                println!("Unable to complete low stock check: {}", e);
+            }
+        }
+    });
+
+    let data = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SCHEDULED_PUBLISH_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = publish_scheduled_revisions(&data).await {
+                println!("Unable to publish scheduled revisions: {}", e);
+            }
+        }
+    });
+
+    let data = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(ORPHANED_IMAGE_CLEANUP_INTERVAL));
+        loop {
+            interval.tick().await;
+            let orphans = match find_orphaned_images(&data).await {
+                Ok(orphans) => orphans,
+                Err(e) => {
+                    println!("Unable to find orphaned images: {}", e);
+                    continue;
+                }
+            };
+            if let Err(e) = delete_orphaned_images(&data, orphans).await {
+                println!("Unable to delete orphaned images: {}", e);
+            }
+        }
+    });
+
+    let data = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(LOGIN_THROTTLE_CLEANUP_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = Mutation::delete_stale_login_throttles(&data.db).await {
+                println!("Unable to delete stale login throttles: {}", e);
+            }
+        }
+    });
+}
+
+fn is_orphaned(object: &StoredObject, referenced: &HashSet<Uuid>) -> bool {
+    image_key_id(&object.key).is_some_and(|hash_id| !referenced.contains(&hash_id))
+}
+
+/// Stored images no product revision or variant shows, older than the grace period
+/// so uploads that are yet to be attached are left alone. Files that aren't images
+/// of ours are never reported.
+pub async fn find_orphaned_images(data: &Arc<AppState>) -> anyhow::Result<Vec<StoredObject>> {
+    let cutoff =
+        Utc::now().naive_utc() - chrono::Duration::hours(data.env.image_cleanup_grace_hours);
+    let objects = data.storage.list().await?;
+    let referenced = Query::find_referenced_image_ids(&data.db).await?;
+
+    Ok(objects
+        .into_iter()
+        .filter(|object| object.last_modified < cutoff)
+        .filter(|object| is_orphaned(object, &referenced))
+        .collect())
+}
+
+/// Deletes the files along with their variant records, returning those deleted
+pub async fn delete_orphaned_images(
+    data: &Arc<AppState>,
+    orphans: Vec<StoredObject>,
+) -> anyhow::Result<Vec<StoredObject>> {
+    // An image may have been attached since the orphans were found
+    let referenced = Query::find_referenced_image_ids(&data.db).await?;
+    let orphans = orphans
+        .into_iter()
+        .filter(|object| is_orphaned(object, &referenced))
+        .collect::<Vec<_>>();
+
+    for object in &orphans {
+        data.storage.delete(&object.key).await?;
+    }
+
+    let hash_ids = orphans
+        .iter()
+        .filter_map(|object| image_key_id(&object.key))
+        .collect::<HashSet<_>>();
+    Mutation::delete_media(&data.db, hash_ids.into_iter().collect()).await?;
+
+    Ok(orphans)
+}
+
+async fn publish_scheduled_revisions(data: &Arc<AppState>) -> Result<(), DbErr> {
+    let revisions = Query::find_due_scheduled_revisions(&data.db, Utc::now().naive_utc()).await?;
+
+    for (revision, product) in revisions {
+        let Some(product) = product else {
+            continue;
+        };
+
+        let revision_id = revision.id;
+        let name = revision.name.to_owned();
+        // One failing revision shouldn't hold back the others due in this run
+        if let Err(e) = Mutation::publish_product_draft(&data.db, product, revision).await {
+            println!(
+                "Unable to publish scheduled revision {}: {}",
+                revision_id, e
+            );
+            continue;
+        }
+
+        let publish_msg = format!("Scheduled revision {} of {} is now live", revision_id, name);
+        data.message_channel
+            .lock()
+            .unwrap()
+            .push_back(publish_msg.as_str().into());
+    }
+
+    Ok(())
+}
+
+async fn check_low_stock(data: &Arc<AppState>) -> Result<(), DbErr> {
+    let products = Product::find()
+        .find_also_related(ProductDetail)
+        .filter(product::Column::LowStockThreshold.is_not_null())
+        .all(&data.db)
+        .await?;
+
+    for (product, product_detail) in products {
+        let threshold = product.low_stock_threshold.unwrap_or_default();
+        // Orders waiting to ship don't leave the ledger, so they're held back here
+        let available = Query::find_product_availability(&data.db, product.id).await?;
+        let low_stock = is_low_stock(available, threshold);
+
+        if low_stock && product.low_stock_alert_date.is_none() {
+            let name = product_detail
+                .map(|detail| detail.name)
+                .unwrap_or(product.short_url.to_owned());
+            let alert_msg = format!(
+                "Low stock: {} has {} available (threshold {})",
+                name, available, threshold
+            );
+
+            data.message_channel
+                .lock()
+                .unwrap()
+                .push_back(alert_msg.as_str().into());
+
+            if let Some(alert_email) = &data.env.alert_email {
+                send_low_stock_alert_email(alert_email, &alert_msg).await;
+            }
+
+            product::ActiveModel {
+                id: ActiveValue::Set(product.id),
+                low_stock_alert_date: ActiveValue::Set(Some(Utc::now().naive_utc())),
+                ..Default::default()
+            }
+            .update(&data.db)
+            .await?;
+        } else if !low_stock && product.low_stock_alert_date.is_some() {
+            product::ActiveModel {
+                id: ActiveValue::Set(product.id),
+                low_stock_alert_date: ActiveValue::Set(None),
+                ..Default::default()
+            }
+            .update(&data.db)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use ::entity::{account_session, login_throttle, refresh_token};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// How long a password reset link can be used for
+pub const PASSWORD_RESET_TOKEN_LIFETIME_MINUTES: i64 = 60;
+
+/// Minimum time between two password reset requests for an account
+pub const PASSWORD_RESET_REQUEST_DELAY_MINUTES: i64 = 60;
+
+/// How long an email verification link can be used for
+pub const EMAIL_VERIFICATION_TOKEN_LIFETIME_HOURS: i64 = 24;
+
+/// Minimum time between two verification emails for an account
+pub const EMAIL_VERIFICATION_RESEND_DELAY_MINUTES: i64 = 5;
+
+/// Audience of email verification tokens, so they can't pass for other tokens signed with
+/// the same secret
+pub const EMAIL_VERIFICATION_AUDIENCE: &str = "email-verification";
+
+/// Signed into email verification links, tied to the address it was sent to
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailVerificationClaims {
+    pub sub: i32,
+    pub aud: String,
+    pub email: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+pub fn encode_verification_token(
+    secret: &[u8],
+    account_id: i32,
+    email: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let claims = EmailVerificationClaims {
+        sub: account_id,
+        aud: EMAIL_VERIFICATION_AUDIENCE.to_owned(),
+        email: email.to_owned(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::hours(EMAIL_VERIFICATION_TOKEN_LIFETIME_HOURS)).timestamp() as usize,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret),
+    )
+    .unwrap()
+}
+
+/// The claims of a verification token, `None` if it is expired, tampered with or not
+/// meant for email verification
+pub fn decode_verification_token(secret: &[u8], token: &str) -> Option<EmailVerificationClaims> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[EMAIL_VERIFICATION_AUDIENCE]);
+    validation.set_required_spec_claims(&["exp", "aud"]);
+    decode::<EmailVerificationClaims>(token, &DecodingKey::from_secret(secret), &validation)
+        .ok()
+        .map(|data| data.claims)
+}
+
+pub const LOGIN_THROTTLE_ACCOUNT: &str = "account";
+pub const LOGIN_THROTTLE_IP: &str = "ip";
+
+pub const LOGIN_EVENT_LOCKOUT: &str = "lockout";
+pub const LOGIN_EVENT_UNLOCK: &str = "unlock";
+
+/// Failures are forgotten once this long has passed without another
+pub const LOGIN_FAILURE_WINDOW_MINUTES: i64 = 60;
+
+pub const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+
+/// Longest wait asked for between attempts before a lockout
+pub const LOGIN_MAX_BACKOFF_SECONDS: i64 = 300;
+
+/// How many failed logins are tolerated for one email address or IP address
+pub struct LoginThrottlePolicy {
+    /// Failures allowed before each attempt has to wait, doubling every time
+    pub free_failures: i32,
+    /// Failures that lock out further attempts for `LOGIN_LOCKOUT_MINUTES`
+    pub lockout_failures: i32,
+}
+
+pub const ACCOUNT_LOGIN_POLICY: LoginThrottlePolicy = LoginThrottlePolicy {
+    free_failures: 3,
+    lockout_failures: 10,
+};
+
+/// Looser than per account as many people can share an address
+pub const IP_LOGIN_POLICY: LoginThrottlePolicy = LoginThrottlePolicy {
+    free_failures: 10,
+    lockout_failures: 50,
+};
+
+/// Failed logins that still count, those older than the failure window are forgotten
+pub fn login_failure_count(throttle: &login_throttle::Model, now: NaiveDateTime) -> i32 {
+    if throttle.last_failure_date + Duration::minutes(LOGIN_FAILURE_WINDOW_MINUTES) <= now {
+        0
+    } else {
+        throttle.failure_count
+    }
+}
+
+/// When the next login attempt may be made, `None` if it may be made now
+pub fn login_retry_date(
+    throttle: &login_throttle::Model,
+    policy: &LoginThrottlePolicy,
+    now: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    if let Some(locked_until) = throttle.locked_until.filter(|date| *date > now) {
+        return Some(locked_until);
+    }
+    if login_failure_count(throttle, now) < policy.free_failures {
+        return None;
+    }
+
+    let exponent = (throttle.failure_count - policy.free_failures).min(16) as u32;
+    let backoff = 2i64.pow(exponent).min(LOGIN_MAX_BACKOFF_SECONDS);
+    Some(throttle.last_failure_date + Duration::seconds(backoff)).filter(|date| *date > now)
+}
+
+/// A random token to hand to the user, only its hash is ever stored
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// What became of a refresh token handed back by a client
+pub enum RefreshOutcome {
+    /// The token was exchanged for a new one on the returned session
+    Rotated(account_session::Model),
+    /// The token had already been exchanged, so its session has been revoked
+    Reused,
+    /// The token is unknown, expired or belongs to an ended session
+    Invalid,
+}
+
+/// Why a refresh token can't be exchanged on its session, `None` if it can. A token
+/// already exchanged only counts as reused while its session is still active.
+pub fn refresh_token_rejection(
+    token: &refresh_token::Model,
+    session: &account_session::Model,
+    now: NaiveDateTime,
+) -> Option<RefreshOutcome> {
+    if session.revoked_date.is_some() || session.expiry_date <= now {
+        Some(RefreshOutcome::Invalid)
+    } else if token.used_date.is_some() {
+        Some(RefreshOutcome::Reused)
+    } else if token.expiry_date <= now {
+        Some(RefreshOutcome::Invalid)
+    } else {
+        None
+    }
+}
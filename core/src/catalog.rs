@@ -0,0 +1,651 @@
+use crate::inventory::stock_movement_query;
+use ::entity::{
+    account, attribute, attribute_option, category, image_variant, media, product,
+    product_attribute, product_category, product_detail, product_image, product_variant,
+    product_variant_image, product_variant_option,
+};
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use sea_orm::{
+    prelude::Uuid,
+    sea_query::{Alias, Expr, Func, Query, SelectStatement, UnionType},
+    ColumnTrait, Condition, FromQueryResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const PRODUCT_STATUS_DRAFT: &str = "draft";
+pub const PRODUCT_STATUS_ACTIVE: &str = "active";
+pub const PRODUCT_STATUS_ARCHIVED: &str = "archived";
+pub const PRODUCT_STATUSES: [&str; 3] = [
+    PRODUCT_STATUS_DRAFT,
+    PRODUCT_STATUS_ACTIVE,
+    PRODUCT_STATUS_ARCHIVED,
+];
+
+pub const REVISION_STATUS_DRAFT: &str = "draft";
+pub const REVISION_STATUS_SCHEDULED: &str = "scheduled";
+pub const REVISION_STATUS_PUBLISHED: &str = "published";
+
+pub const PRODUCT_EVENT_REVISION_PUBLISHED: &str = "revision_published";
+pub const PRODUCT_EVENT_REVISION_SCHEDULED: &str = "revision_scheduled";
+pub const PRODUCT_EVENT_REVISION_ROLLED_BACK: &str = "revision_rolled_back";
+pub const PRODUCT_EVENT_STATUS_CHANGED: &str = "status_changed";
+
+pub const ATTRIBUTE_KIND_TEXT: &str = "text";
+pub const ATTRIBUTE_KIND_SELECT: &str = "select";
+pub const ATTRIBUTE_KIND_COLOR_SWATCH: &str = "color_swatch";
+pub const ATTRIBUTE_KIND_NUMBER: &str = "number";
+pub const ATTRIBUTE_KINDS: [&str; 4] = [
+    ATTRIBUTE_KIND_TEXT,
+    ATTRIBUTE_KIND_SELECT,
+    ATTRIBUTE_KIND_COLOR_SWATCH,
+    ATTRIBUTE_KIND_NUMBER,
+];
+
+pub const DEFAULT_PRODUCTS_PER_PAGE: u64 = 20;
+pub const MAX_PRODUCTS_PER_PAGE: u64 = 100;
+
+pub const DEFAULT_MEDIA_PER_PAGE: u64 = 30;
+pub const MAX_MEDIA_PER_PAGE: u64 = 100;
+
+/// Upper bounds of the price ranges offered as facets, the last range is open ended
+pub const PRICE_FACET_BOUNDARIES: [i64; 5] = [25, 50, 100, 250, 500];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProductSort {
+    #[default]
+    Newest,
+    Name,
+    PriceAsc,
+    PriceDesc,
+}
+
+impl ProductSort {
+    pub fn parse(sort: &str) -> Option<Self> {
+        match sort {
+            "newest" => Some(ProductSort::Newest),
+            "name" => Some(ProductSort::Name),
+            "price_asc" => Some(ProductSort::PriceAsc),
+            "price_desc" => Some(ProductSort::PriceDesc),
+            _ => None,
+        }
+    }
+}
+
+/// Products the public may see: active and inside their publish window
+pub fn visible_product_condition(now: NaiveDateTime) -> Condition {
+    Condition::all()
+        .add(product::Column::Status.eq(PRODUCT_STATUS_ACTIVE))
+        .add(
+            Condition::any()
+                .add(product::Column::PublishDate.is_null())
+                .add(product::Column::PublishDate.lte(now)),
+        )
+        .add(
+            Condition::any()
+                .add(product::Column::UnpublishDate.is_null())
+                .add(product::Column::UnpublishDate.gt(now)),
+        )
+}
+
+pub fn is_product_visible(product: &product::Model, now: NaiveDateTime) -> bool {
+    product.status == PRODUCT_STATUS_ACTIVE
+        && product.publish_date.is_none_or(|date| date <= now)
+        && product.unpublish_date.is_none_or(|date| date > now)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilters {
+    /// Category ids a product must belong to one of, already expanded to include descendants
+    pub category_ids: Option<Vec<i32>>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub in_stock: bool,
+    /// Selected attribute options, a product must carry one of the selected options of each attribute
+    pub option_ids: Vec<i32>,
+}
+
+/// Which group of filters a facet count leaves out, so alternatives to a selection stay visible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FacetGroup {
+    Category,
+    Attribute(i32),
+    Price,
+}
+
+/// Attribute options each product carries as `product_id` and `option_id` rows, either
+/// through an active variant or through an attribute value on its live revision naming
+/// the option
+pub(crate) fn product_option_query() -> SelectStatement {
+    let attribute_content = Func::lower(Func::cust(Alias::new("trim")).arg(Expr::col((
+        product_attribute::Entity,
+        product_attribute::Column::Content,
+    ))));
+    let attribute_values = Query::select()
+        .column((product::Entity, product::Column::Id))
+        .column((attribute_option::Entity, attribute_option::Column::Id))
+        .from(product_attribute::Entity)
+        .inner_join(
+            product::Entity,
+            Expr::col((product::Entity, product::Column::RevisionId)).equals((
+                product_attribute::Entity,
+                product_attribute::Column::ProductId,
+            )),
+        )
+        .inner_join(
+            attribute_option::Entity,
+            Condition::all()
+                .add(
+                    Expr::col((
+                        attribute_option::Entity,
+                        attribute_option::Column::AttributeId,
+                    ))
+                    .equals((
+                        product_attribute::Entity,
+                        product_attribute::Column::AttributeId,
+                    )),
+                )
+                .add(
+                    Condition::any()
+                        .add(
+                            Expr::expr(Func::lower(Expr::col((
+                                attribute_option::Entity,
+                                attribute_option::Column::Label,
+                            ))))
+                            .eq(attribute_content.to_owned()),
+                        )
+                        .add(
+                            Expr::expr(Func::lower(Expr::col((
+                                attribute_option::Entity,
+                                attribute_option::Column::Content,
+                            ))))
+                            .eq(attribute_content),
+                        ),
+                ),
+        )
+        .to_owned();
+
+    Query::select()
+        .expr_as(
+            Expr::col((product_variant::Entity, product_variant::Column::ProductId)),
+            Alias::new("product_id"),
+        )
+        .expr_as(
+            Expr::col((
+                product_variant_option::Entity,
+                product_variant_option::Column::AttributeOptionId,
+            )),
+            Alias::new("option_id"),
+        )
+        .from(product_variant_option::Entity)
+        .inner_join(
+            product_variant::Entity,
+            Expr::col((product_variant::Entity, product_variant::Column::Id)).equals((
+                product_variant_option::Entity,
+                product_variant_option::Column::VariantId,
+            )),
+        )
+        .and_where(Expr::col((product_variant::Entity, product_variant::Column::Active)).eq(true))
+        .union(UnionType::Distinct, attribute_values)
+        .to_owned()
+}
+
+/// Whether an attribute value written on a revision names the option, matching
+/// `product_option_query` by comparing its trimmed content to the option's label or content
+/// regardless of case
+pub fn is_option_selected(option: &attribute_option::Model, content: &str) -> bool {
+    let content = content.trim().to_lowercase();
+    option.label.to_lowercase() == content || option.content.to_lowercase() == content
+}
+
+/// Visible products matching the filters, with the selection of the `except` group left out
+/// so alternatives to it stay visible. Expects the live revision to be joined.
+/// `selected_options` maps attribute ids to the options selected for them.
+pub(crate) fn product_filter_condition(
+    filters: &ProductFilters,
+    selected_options: &HashMap<i32, Vec<i32>>,
+    except: Option<FacetGroup>,
+    now: NaiveDateTime,
+) -> Condition {
+    let mut condition = visible_product_condition(now);
+
+    if except != Some(FacetGroup::Category) {
+        if let Some(category_ids) = &filters.category_ids {
+            condition = condition.add(
+                product::Column::Id.in_subquery(
+                    Query::select()
+                        .column(product_category::Column::ProductId)
+                        .from(product_category::Entity)
+                        .and_where(
+                            product_category::Column::CategoryId.is_in(category_ids.to_owned()),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+    }
+
+    if except != Some(FacetGroup::Price) {
+        if let Some(min_price) = filters.min_price {
+            condition = condition.add(product_detail::Column::Price.gte(min_price));
+        }
+        if let Some(max_price) = filters.max_price {
+            condition = condition.add(product_detail::Column::Price.lte(max_price));
+        }
+    }
+
+    if filters.in_stock {
+        condition = condition.add(
+            product::Column::Id.in_subquery(
+                Query::select()
+                    .column(Alias::new("product_id"))
+                    .from_subquery(stock_movement_query(), Alias::new("stock_movement"))
+                    .group_by_col(Alias::new("product_id"))
+                    .and_having(Expr::expr(Func::sum(Expr::col(Alias::new("amount")))).gt(0))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    for (attribute_id, option_ids) in selected_options {
+        if except == Some(FacetGroup::Attribute(*attribute_id)) {
+            continue;
+        }
+        condition = condition.add(
+            product::Column::Id.in_subquery(
+                Query::select()
+                    .column(Alias::new("product_id"))
+                    .from_subquery(product_option_query(), Alias::new("product_option"))
+                    .and_where(Expr::col(Alias::new("option_id")).is_in(option_ids.to_owned()))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    condition
+}
+
+/// Counts of matching products for every value the storefront can filter on
+#[derive(Debug, Clone)]
+pub struct ProductFacets {
+    pub categories: Vec<(category::Model, u64)>,
+    pub attributes: Vec<AttributeFacet>,
+    pub prices: Vec<PriceFacet>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttributeFacet {
+    pub attribute: attribute::Model,
+    pub options: Vec<(attribute_option::Model, u64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceFacet {
+    pub min: Decimal,
+    pub max: Option<Decimal>,
+    pub count: u64,
+}
+
+impl PriceFacet {
+    pub fn contains(&self, price: Decimal) -> bool {
+        price >= self.min && self.max.is_none_or(|max| price < max)
+    }
+}
+
+/// Empty price ranges built from `PRICE_FACET_BOUNDARIES`
+pub fn price_facets() -> Vec<PriceFacet> {
+    let mut min = Decimal::ZERO;
+    let mut facets = Vec::new();
+    for boundary in PRICE_FACET_BOUNDARIES {
+        let max = Decimal::from(boundary);
+        facets.push(PriceFacet {
+            min,
+            max: Some(max),
+            count: 0,
+        });
+        min = max;
+    }
+    facets.push(PriceFacet {
+        min,
+        max: None,
+        count: 0,
+    });
+    facets
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, FromQueryResult)]
+pub struct ListedProduct {
+    pub id: i32,
+    pub short_url: String,
+    pub revision_id: i32,
+    pub category_id: Option<i32>,
+    pub category_label: Option<String>,
+    pub name: String,
+    pub price: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProductPage {
+    pub products: Vec<(ListedProduct, Option<Uuid>)>,
+    pub total_items: u64,
+    pub total_pages: u64,
+}
+
+/// A category with its descendants, siblings ordered by position
+#[derive(Debug, Clone)]
+pub struct CategoryNode {
+    pub category: category::Model,
+    pub children: Vec<CategoryNode>,
+}
+
+/// Nests a flat list of categories below their parents
+pub fn build_category_tree(mut categories: Vec<category::Model>) -> Vec<CategoryNode> {
+    categories.sort_by(|a, b| (a.position, &a.label, a.id).cmp(&(b.position, &b.label, b.id)));
+
+    fn children_of(categories: &[category::Model], parent_id: Option<i32>) -> Vec<CategoryNode> {
+        categories
+            .iter()
+            .filter(|category| category.parent_id == parent_id)
+            .map(|category| CategoryNode {
+                category: category.to_owned(),
+                children: children_of(categories, Some(category.id)),
+            })
+            .collect()
+    }
+
+    children_of(&categories, None)
+}
+
+/// Values written when creating or moving a category
+#[derive(Debug, Clone)]
+pub struct CategoryInput {
+    pub label: String,
+    pub parent_id: Option<i32>,
+    /// Place among the new siblings, appended after them when unset
+    pub position: Option<i32>,
+}
+
+/// Everything needed to present a product, taken from its live revision
+#[derive(Debug, Clone)]
+pub struct ProductDetails {
+    pub product: product::Model,
+    pub detail: product_detail::Model,
+    pub images: Vec<product_image::Model>,
+    pub categories: Vec<category::Model>,
+    pub attributes: Vec<(
+        product_attribute::Model,
+        attribute::Model,
+        Vec<attribute_option::Model>,
+    )>,
+    pub available: i64,
+    pub variants: Vec<VariantDetails>,
+}
+
+/// A purchasable combination of option values with its own SKU, price and stock
+#[derive(Debug, Clone)]
+pub struct VariantDetails {
+    pub variant: product_variant::Model,
+    pub options: Vec<(attribute::Model, attribute_option::Model)>,
+    pub images: Vec<product_variant_image::Model>,
+    pub available: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantInput {
+    pub sku: String,
+    /// Overrides the price of the live revision when set
+    pub price: Option<Decimal>,
+    pub active: bool,
+    pub image_ids: Vec<Uuid>,
+}
+
+/// Values written to a new product revision
+#[derive(Debug, Clone)]
+pub struct RevisionInput {
+    pub name: String,
+    pub description: String,
+    pub price: Decimal,
+    pub upc: Option<String>,
+    pub real_weight: Option<i32>,
+    pub ship_weight: Option<i32>,
+    pub attributes: Vec<(i32, String)>,
+    pub images: Vec<ProductImageInput>,
+}
+
+/// An uploaded image shown on a product, in gallery order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductImageInput {
+    pub hash_id: Uuid,
+    /// Kept from the parent revision when unset, an empty text clears it
+    pub alt_text: Option<String>,
+}
+
+/// A stored resize of an uploaded image
+#[derive(Debug, Clone)]
+pub struct ImageVariantInput {
+    pub name: String,
+    pub key: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// What is recorded about an upload in the media library
+#[derive(Debug, Clone)]
+pub struct MediaInput {
+    pub hash_id: Uuid,
+    pub account_id: Option<i32>,
+    pub file_name: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    /// Bytes of the file as uploaded
+    pub size: i64,
+    pub variants: Vec<ImageVariantInput>,
+}
+
+/// An upload in the media library with who uploaded it and how often it is shown
+#[derive(Debug, Clone)]
+pub struct MediaDetails {
+    pub media: media::Model,
+    pub uploader: Option<account::Model>,
+    pub variants: Vec<image_variant::Model>,
+    /// Product revisions and variants showing the image
+    pub usage: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MediaPage {
+    pub media: Vec<MediaDetails>,
+    pub total_items: u64,
+    pub total_pages: u64,
+}
+
+/// Checks an option's content fits its attribute's kind: a `#rgb` or `#rrggbb` hex
+/// colour for swatches, a number for numeric attributes and any text otherwise
+pub fn validate_attribute_content(kind: &str, content: &str) -> Result<(), String> {
+    match kind {
+        ATTRIBUTE_KIND_COLOR_SWATCH => {
+            let hex = content.strip_prefix('#').unwrap_or_default();
+            if (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(())
+            } else {
+                Err(format!("{} is not a hex colour such as #ff0000", content))
+            }
+        }
+        ATTRIBUTE_KIND_NUMBER => content
+            .parse::<Decimal>()
+            .map(|_| ())
+            .map_err(|_| format!("{} is not a number", content)),
+        _ if content.trim().is_empty() => Err("Option content cannot be empty".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Values written to an attribute, `options` being its complete list in display order
+#[derive(Debug, Clone)]
+pub struct AttributeInput {
+    pub label: String,
+    pub kind: String,
+    pub options: Vec<AttributeOptionInput>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttributeOptionInput {
+    /// Existing option to update, a new option is created when unset
+    pub id: Option<i32>,
+    pub label: String,
+    pub content: String,
+}
+
+/// Values applied when a product is created or edited, revision content included
+#[derive(Debug, Clone)]
+pub struct ProductInput {
+    pub short_url: String,
+    /// Left unchanged on edit when not given, active on creation
+    pub status: Option<String>,
+    pub publish_date: Option<NaiveDateTime>,
+    pub unpublish_date: Option<NaiveDateTime>,
+    pub category_ids: Vec<i32>,
+    /// One of `category_ids`, the first of them when not given
+    pub primary_category_id: Option<i32>,
+    pub allow_back_order: bool,
+    pub allow_restock_notifications: bool,
+    pub revision: RevisionInput,
+}
+
+impl ProductInput {
+    pub fn primary_category_id(&self) -> Option<i32> {
+        self.primary_category_id
+            .filter(|category_id| self.category_ids.contains(category_id))
+            .or(self.category_ids.first().copied())
+    }
+}
+
+/// A single revision of a product with the attribute values and images saved with it
+#[derive(Debug, Clone)]
+pub struct ProductRevision {
+    pub detail: product_detail::Model,
+    pub attributes: Vec<product_attribute::Model>,
+    pub images: Vec<product_image::Model>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionChange {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl ProductRevision {
+    pub fn to_input(&self) -> RevisionInput {
+        RevisionInput {
+            name: self.detail.name.to_owned(),
+            description: self.detail.description.to_owned(),
+            price: self.detail.price,
+            upc: self.detail.upc.to_owned(),
+            real_weight: self.detail.real_weight,
+            ship_weight: self.detail.ship_weight,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|attribute| (attribute.attribute_id, attribute.content.to_owned()))
+                .collect(),
+            images: self
+                .images
+                .iter()
+                .map(|image| ProductImageInput {
+                    hash_id: image.hash_id,
+                    alt_text: Some(image.alt_text.to_owned().unwrap_or_default()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Fields that differ between this revision and another, attributes keyed by attribute id
+    pub fn diff(&self, other: &ProductRevision) -> Vec<RevisionChange> {
+        let mut changes = vec![];
+        let mut compare = |field: String, from: Option<String>, to: Option<String>| {
+            if from != to {
+                changes.push(RevisionChange { field, from, to });
+            }
+        };
+
+        compare(
+            "name".to_owned(),
+            Some(self.detail.name.to_owned()),
+            Some(other.detail.name.to_owned()),
+        );
+        compare(
+            "description".to_owned(),
+            Some(self.detail.description.to_owned()),
+            Some(other.detail.description.to_owned()),
+        );
+        compare(
+            "price".to_owned(),
+            Some(self.detail.price.to_string()),
+            Some(other.detail.price.to_string()),
+        );
+        compare(
+            "upc".to_owned(),
+            self.detail.upc.to_owned(),
+            other.detail.upc.to_owned(),
+        );
+        compare(
+            "realWeight".to_owned(),
+            self.detail.real_weight.map(|weight| weight.to_string()),
+            other.detail.real_weight.map(|weight| weight.to_string()),
+        );
+        compare(
+            "shipWeight".to_owned(),
+            self.detail.ship_weight.map(|weight| weight.to_string()),
+            other.detail.ship_weight.map(|weight| weight.to_string()),
+        );
+
+        let mut attribute_ids: Vec<i32> = self
+            .attributes
+            .iter()
+            .chain(other.attributes.iter())
+            .map(|attribute| attribute.attribute_id)
+            .collect();
+        attribute_ids.sort_unstable();
+        attribute_ids.dedup();
+
+        let attribute_content = |revision: &ProductRevision, attribute_id: i32| {
+            revision
+                .attributes
+                .iter()
+                .find(|attribute| attribute.attribute_id == attribute_id)
+                .map(|attribute| attribute.content.to_owned())
+        };
+
+        for attribute_id in attribute_ids {
+            compare(
+                format!("attribute:{}", attribute_id),
+                attribute_content(self, attribute_id),
+                attribute_content(other, attribute_id),
+            );
+        }
+
+        let image_list = |revision: &ProductRevision| {
+            revision
+                .images
+                .iter()
+                .map(|image| match &image.alt_text {
+                    Some(alt_text) => format!("{} ({})", image.hash_id, alt_text),
+                    None => image.hash_id.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        compare(
+            "images".to_owned(),
+            Some(image_list(self)),
+            Some(image_list(other)),
+        );
+
+        changes
+    }
+}
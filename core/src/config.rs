@@ -3,23 +3,132 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
     pub jwt_expiry: i64,
+    /// How long a session can go without being refreshed before it ends
+    pub refresh_token_expiry_days: i64,
+    /// Whether accounts must verify their email before using order features
+    pub require_verified_email: bool,
+    /// Whether the client address is taken from the last `X-Forwarded-For` entry, only
+    /// safe behind a proxy that sets it
+    pub trust_forwarded_for: bool,
     pub web_host: String,
     pub api_port: u16,
+    pub alert_email: Option<String>,
+    pub storage: StorageConfig,
+    pub image_variants: Vec<ImageVariantSize>,
+    /// How long an upload may stay unused before it is cleaned up
+    pub image_cleanup_grace_hours: i64,
 }
 
+/// Chosen with `STORAGE_BACKEND`, either `s3` (the default) or `local`
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    S3 {
+        bucket: String,
+    },
+    /// Files written below `path` and served by the API under `public_url`
+    Local {
+        path: String,
+        public_url: String,
+    },
+}
+
+impl StorageConfig {
+    fn init(api_port: u16) -> StorageConfig {
+        let backend = std::env::var("STORAGE_BACKEND").unwrap_or("s3".to_string());
+        match backend.as_str() {
+            "s3" => StorageConfig::S3 {
+                bucket: std::env::var("S3_BUCKET").unwrap_or("lemonseeds".to_string()),
+            },
+            "local" => StorageConfig::Local {
+                path: std::env::var("LOCAL_STORAGE_PATH").unwrap_or("uploads".to_string()),
+                public_url: std::env::var("LOCAL_STORAGE_URL")
+                    .unwrap_or(format!("http://localhost:{}/uploads", api_port)),
+            },
+            _ => panic!("STORAGE_BACKEND must be s3 or local, got {}", backend),
+        }
+    }
+}
+
+/// A resized copy made of every upload, fitted within `max_width` by `max_height`
+#[derive(Debug, Clone)]
+pub struct ImageVariantSize {
+    pub name: String,
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+const DEFAULT_IMAGE_VARIANTS: &str = "thumbnail:200x200,card:600x600,full:1600x1600";
+
+impl ImageVariantSize {
+    /// Reads `IMAGE_VARIANTS`, a comma separated list of `name:WIDTHxHEIGHT`
+    fn init() -> Vec<ImageVariantSize> {
+        let variants =
+            std::env::var("IMAGE_VARIANTS").unwrap_or(DEFAULT_IMAGE_VARIANTS.to_string());
+        variants
+            .split(',')
+            .map(str::trim)
+            .filter(|variant| !variant.is_empty())
+            .map(|variant| {
+                let (name, size) = variant
+                    .split_once(':')
+                    .and_then(|(name, size)| Some((name, size.split_once('x')?)))
+                    .and_then(|(name, (width, height))| {
+                        Some((name, (width.parse().ok()?, height.parse().ok()?)))
+                    })
+                    .filter(|(name, (width, height))| !name.is_empty() && *width > 0 && *height > 0)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "IMAGE_VARIANTS entries must be name:WIDTHxHEIGHT, got {}",
+                            variant
+                        )
+                    });
+                ImageVariantSize {
+                    name: name.to_string(),
+                    max_width: size.0,
+                    max_height: size.1,
+                }
+            })
+            .collect()
+    }
+}
+
+const DEFAULT_IMAGE_CLEANUP_GRACE_HOURS: i64 = 24;
+const DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS: i64 = 30;
+
 impl Config {
     pub fn init() -> Config {
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
         let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET is not set");
         let jwt_expiry = std::env::var("JWT_EXPIRY").expect("JWT_EXPIRY is not set");
+        let refresh_token_expiry_days = std::env::var("REFRESH_TOKEN_EXPIRY_DAYS")
+            .map(|days| days.parse().unwrap())
+            .unwrap_or(DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS);
+        let require_verified_email = std::env::var("REQUIRE_VERIFIED_EMAIL")
+            .map(|require| require.parse().unwrap())
+            .unwrap_or(true);
+        let trust_forwarded_for = std::env::var("TRUST_FORWARDED_FOR")
+            .map(|trust| trust.parse().unwrap())
+            .unwrap_or(false);
         let web_host = std::env::var("WEB_HOST").expect("WEB_HOST is not set");
         let api_port = std::env::var("API_PORT").expect("API_PORT is not set");
+        let api_port = api_port.parse().unwrap();
+        let alert_email = std::env::var("ALERT_EMAIL").ok();
+        let image_cleanup_grace_hours = std::env::var("IMAGE_CLEANUP_GRACE_HOURS")
+            .map(|hours| hours.parse().unwrap())
+            .unwrap_or(DEFAULT_IMAGE_CLEANUP_GRACE_HOURS);
         Config {
             database_url,
             jwt_secret,
             jwt_expiry: jwt_expiry.parse().unwrap(),
+            refresh_token_expiry_days,
+            require_verified_email,
+            trust_forwarded_for,
             web_host,
-            api_port: api_port.parse().unwrap(),
+            api_port,
+            alert_email,
+            storage: StorageConfig::init(api_port),
+            image_variants: ImageVariantSize::init(),
+            image_cleanup_grace_hours,
         }
     }
 }
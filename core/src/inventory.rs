@@ -0,0 +1,114 @@
+use ::entity::{location, order, order_item, product_detail, stock};
+use sea_orm::{
+    sea_query::{Alias, Expr, Query, SelectStatement, UnionType},
+    ColumnTrait, Condition,
+};
+use serde::{Deserialize, Serialize};
+
+/// Order statuses whose items are still waiting on stock to be fulfilled.
+pub const OPEN_ORDER_STATUSES: [&str; 2] = ["created", "processing"];
+pub const CANCELLED_ORDER_STATUS: &str = "cancelled";
+
+/// Number of days of order history used to calculate sales velocity.
+pub const SALES_VELOCITY_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductInventory {
+    pub product_id: i32,
+    pub short_url: String,
+    pub name: String,
+    pub low_stock_threshold: Option<i32>,
+    pub on_hand: i64,
+    pub reserved: i64,
+    pub backordered: i64,
+    pub sales_velocity: f64,
+}
+
+/// Order items that hold back stock at the location they will be fulfilled from
+pub(crate) fn reserved_order_item_condition() -> Condition {
+    Condition::all()
+        .add(order::Column::Status.is_in(OPEN_ORDER_STATUSES))
+        .add(order_item::Column::LocationId.is_not_null())
+}
+
+/// Every change to the stock of a product as (product_id, amount) rows, with stock
+/// received as positive amounts and quantities reserved by open orders as negative
+pub(crate) fn stock_movement_query() -> SelectStatement {
+    let reservations = Query::select()
+        .column((product_detail::Entity, product_detail::Column::ProductId))
+        .expr_as(
+            Expr::col((order_item::Entity, order_item::Column::Qty)).mul(-1),
+            Alias::new("amount"),
+        )
+        .from(order_item::Entity)
+        .inner_join(
+            order::Entity,
+            Expr::col((order::Entity, order::Column::Id))
+                .equals((order_item::Entity, order_item::Column::OrderId)),
+        )
+        .inner_join(
+            product_detail::Entity,
+            Expr::col((product_detail::Entity, product_detail::Column::Id))
+                .equals((order_item::Entity, order_item::Column::ProductId)),
+        )
+        .cond_where(reserved_order_item_condition())
+        .to_owned();
+
+    Query::select()
+        .column((stock::Entity, stock::Column::ProductId))
+        .column((stock::Entity, stock::Column::Amount))
+        .from(stock::Entity)
+        .union(UnionType::All, reservations)
+        .to_owned()
+}
+
+pub const STOCK_KIND_RECEIPT: &str = "receipt";
+pub const STOCK_KIND_TRANSFER: &str = "transfer";
+
+/// What became of a request to move stock between locations
+pub enum TransferOutcome {
+    /// The outgoing and incoming ledger entries were recorded
+    Transferred(stock::Model, stock::Model),
+    /// The source location only has this much available, so nothing was moved
+    Insufficient(i64),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationInventory {
+    pub location_id: i32,
+    pub label: String,
+    pub priority: i32,
+    pub on_hand: i64,
+    pub reserved: i64,
+    pub available: i64,
+}
+
+impl LocationInventory {
+    pub fn new(location: location::Model, on_hand: i64, reserved: i64) -> Self {
+        Self {
+            location_id: location.id,
+            label: location.label,
+            priority: location.priority,
+            on_hand,
+            reserved,
+            available: on_hand - reserved,
+        }
+    }
+}
+
+/// Stock across every location that is not already promised to an open order
+pub fn total_available(inventory: &[LocationInventory]) -> i64 {
+    inventory.iter().map(|location| location.available).sum()
+}
+
+/// Whether receiving stock brings back a product that orders had sold out
+pub fn is_back_in_stock(previous_available: i64, received: i64) -> bool {
+    previous_available <= 0 && previous_available + received > 0
+}
+
+/// Whether what is left to sell has dropped below a product's low stock threshold
+pub fn is_low_stock(available: i64, threshold: i32) -> bool {
+    available < threshold as i64
+}
@@ -1,18 +1,25 @@
+pub mod auth;
+pub mod catalog;
 mod config;
 pub mod ecommerce;
+pub mod inventory;
 mod mutation;
 pub mod payment_processing;
+pub mod permission;
 mod query;
+pub mod search;
+pub mod storage;
 
 use std::{
     collections::VecDeque,
     sync::{Arc, Mutex},
 };
 
-pub use config::Config;
+pub use config::{Config, ImageVariantSize, StorageConfig};
 pub use mutation::*;
 use payment_processing::manager::PaymentProcessor;
 pub use query::*;
+use storage::StorageBackend;
 
 pub use sea_orm;
 use sea_orm::DatabaseConnection;
@@ -23,4 +30,5 @@ pub struct AppState {
     pub env: Config,
     pub message_channel: Arc<Mutex<VecDeque<Arc<str>>>>,
     pub payment_processor: Arc<dyn PaymentProcessor>,
+    pub storage: Arc<dyn StorageBackend>,
 }
@@ -1,50 +1,1718 @@
-use ::entity::{product, product::Entity as Product};
-use sea_orm::*;
+use crate::{
+    auth::{
+        login_failure_count, login_retry_date, refresh_token_rejection, LoginThrottlePolicy,
+        RefreshOutcome, LOGIN_FAILURE_WINDOW_MINUTES, LOGIN_LOCKOUT_MINUTES,
+        PASSWORD_RESET_TOKEN_LIFETIME_MINUTES,
+    },
+    catalog::{
+        is_option_selected, AttributeInput, CategoryInput, MediaInput, ProductImageInput,
+        ProductInput, RevisionInput, VariantInput, PRODUCT_EVENT_REVISION_PUBLISHED,
+        PRODUCT_EVENT_REVISION_ROLLED_BACK, PRODUCT_EVENT_REVISION_SCHEDULED,
+        PRODUCT_EVENT_STATUS_CHANGED, PRODUCT_STATUS_ACTIVE, REVISION_STATUS_DRAFT,
+        REVISION_STATUS_PUBLISHED, REVISION_STATUS_SCHEDULED,
+    },
+    inventory::{TransferOutcome, STOCK_KIND_RECEIPT, STOCK_KIND_TRANSFER},
+    permission::{Permission, RoleDetails, RoleInput},
+    search::REFRESH_SEARCH_VECTOR_SQL,
+    Query,
+};
+use ::entity::{
+    account, account_role, account_role::Entity as AccountRole, account_session,
+    account_session::Entity as AccountSession, attribute, attribute::Entity as Attribute,
+    attribute_option, attribute_option::Entity as AttributeOption, category,
+    category::Entity as Category, image_variant, image_variant::Entity as ImageVariant, location,
+    location::Entity as Location, login_event, login_throttle,
+    login_throttle::Entity as LoginThrottle, media, media::Entity as Media, password_reset_token,
+    password_reset_token::Entity as PasswordResetToken, product, product::Entity as Product,
+    product_attribute, product_attribute::Entity as ProductAttribute, product_category,
+    product_category::Entity as ProductCategory, product_detail,
+    product_detail::Entity as ProductDetail, product_event, product_event::Entity as ProductEvent,
+    product_image, product_image::Entity as ProductImage, product_variant,
+    product_variant::Entity as ProductVariant, product_variant_image,
+    product_variant_image::Entity as ProductVariantImage, product_variant_option,
+    product_variant_option::Entity as ProductVariantOption, refresh_token,
+    refresh_token::Entity as RefreshToken, restock_subscription,
+    restock_subscription::Entity as RestockSubscription, role, role::Entity as Role,
+    role_permission, role_permission::Entity as RolePermission, stock, stock::Entity as Stock,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{prelude::Uuid, sea_query::Expr, *};
 
 pub struct Mutation;
 
 impl Mutation {
     pub async fn create_product(
         db: &DbConn,
-        new_data: product::Model,
-    ) -> Result<product::ActiveModel, DbErr> {
-        product::ActiveModel {
+        new_data: ProductInput,
+        initial_stock: i32,
+    ) -> Result<product::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let revision = Self::create_product_revision(
+            &txn,
+            &new_data.revision,
+            None,
+            None,
+            REVISION_STATUS_PUBLISHED,
+        )
+        .await?;
+
+        let primary_category_id = new_data.primary_category_id();
+        let product = Product::insert(product::ActiveModel {
+            short_url: Set(new_data.short_url),
+            status: Set(new_data.status.unwrap_or(PRODUCT_STATUS_ACTIVE.to_owned())),
+            publish_date: Set(new_data.publish_date),
+            unpublish_date: Set(new_data.unpublish_date),
+            category_id: Set(primary_category_id),
+            revision_id: Set(revision.id),
+            allow_back_order: Set(new_data.allow_back_order),
+            allow_restock_notifications: Set(new_data.allow_restock_notifications),
             ..Default::default()
+        })
+        .exec_with_returning(&txn)
+        .await?;
+
+        Self::set_product_categories(&txn, product.id, &new_data.category_ids).await?;
+
+        let mut revision: product_detail::ActiveModel = revision.into();
+        revision.product_id = Set(Some(product.id));
+        revision.update(&txn).await?;
+
+        if initial_stock > 0 {
+            let location = Location::find()
+                .order_by_asc(location::Column::Priority)
+                .order_by_asc(location::Column::Id)
+                .one(&txn)
+                .await?
+                .ok_or(DbErr::Custom("Cannot find a stock location.".to_owned()))?;
+
+            Stock::insert(stock::ActiveModel {
+                product_id: Set(product.id),
+                location_id: Set(location.id),
+                amount: Set(initial_stock),
+                kind: Set(STOCK_KIND_RECEIPT.to_owned()),
+                addition_date: Set(Utc::now().naive_utc()),
+                ..Default::default()
+            })
+            .exec(&txn)
+            .await?;
         }
-        .save(db)
-        .await
+
+        txn.commit().await?;
+
+        Ok(product)
+    }
+
+    /// Writes a product revision along with the attribute values and images belonging to it
+    async fn create_product_revision<C: ConnectionTrait>(
+        db: &C,
+        new_data: &RevisionInput,
+        product_id: Option<i32>,
+        parent_id: Option<i32>,
+        status: &str,
+    ) -> Result<product_detail::Model, DbErr> {
+        let revision = ProductDetail::insert(product_detail::ActiveModel {
+            name: Set(new_data.name.to_owned()),
+            description: Set(new_data.description.to_owned()),
+            price: Set(new_data.price),
+            upc: Set(new_data.upc.to_owned()),
+            real_weight: Set(new_data.real_weight),
+            ship_weight: Set(new_data.ship_weight),
+            parent_id: Set(parent_id),
+            product_id: Set(product_id),
+            status: Set(status.to_owned()),
+            creation_date: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await?;
+
+        for (attribute_id, content) in &new_data.attributes {
+            ProductAttribute::insert(product_attribute::ActiveModel {
+                product_id: Set(revision.id),
+                attribute_id: Set(*attribute_id),
+                content: Set(content.to_owned()),
+                ..Default::default()
+            })
+            .exec(db)
+            .await?;
+        }
+
+        let parent_images = match parent_id {
+            Some(parent_id) => {
+                ProductImage::find()
+                    .filter(product_image::Column::ProductId.eq(parent_id))
+                    .all(db)
+                    .await?
+            }
+            None => vec![],
+        };
+
+        for (position, image) in new_data.images.iter().enumerate() {
+            let alt_text = match &image.alt_text {
+                Some(alt_text) => Some(alt_text.trim().to_owned()).filter(|text| !text.is_empty()),
+                None => parent_images
+                    .iter()
+                    .find(|parent_image| parent_image.hash_id == image.hash_id)
+                    .and_then(|parent_image| parent_image.alt_text.to_owned()),
+            };
+
+            ProductImage::insert(product_image::ActiveModel {
+                hash_id: Set(image.hash_id),
+                product_id: Set(revision.id),
+                position: Set(position as i32),
+                alt_text: Set(alt_text),
+                ..Default::default()
+            })
+            .exec(db)
+            .await?;
+        }
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            REFRESH_SEARCH_VECTOR_SQL,
+            [revision.id.into()],
+        ))
+        .await?;
+
+        Ok(revision)
     }
 
+    /// Edits are written as a new revision so orders keep pointing at what was sold
     pub async fn update_product_by_id(
         db: &DbConn,
         id: i32,
-        new_data: product::Model,
+        new_data: ProductInput,
     ) -> Result<product::Model, DbErr> {
-        let post: product::ActiveModel = Product::find_by_id(id)
-            .one(db)
+        let txn = db.begin().await?;
+
+        let product = Product::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find product.".to_owned()))?;
+
+        let revision = Self::create_product_revision(
+            &txn,
+            &new_data.revision,
+            Some(product.id),
+            Some(product.revision_id),
+            REVISION_STATUS_PUBLISHED,
+        )
+        .await?;
+
+        Self::record_product_event(
+            &txn,
+            product.id,
+            Some(revision.id),
+            PRODUCT_EVENT_REVISION_PUBLISHED,
+        )
+        .await?;
+
+        if let Some(status) = &new_data.status {
+            if *status != product.status {
+                Self::record_product_event(&txn, product.id, None, PRODUCT_EVENT_STATUS_CHANGED)
+                    .await?;
+            }
+        }
+
+        let primary_category_id = new_data.primary_category_id();
+        let mut product: product::ActiveModel = product.into();
+        product.short_url = Set(new_data.short_url);
+        if let Some(status) = new_data.status {
+            product.status = Set(status);
+        }
+        product.publish_date = Set(new_data.publish_date);
+        product.unpublish_date = Set(new_data.unpublish_date);
+        product.category_id = Set(primary_category_id);
+        product.revision_id = Set(revision.id);
+        product.allow_back_order = Set(new_data.allow_back_order);
+        product.allow_restock_notifications = Set(new_data.allow_restock_notifications);
+        let product = product.update(&txn).await?;
+
+        Self::set_product_categories(&txn, product.id, &new_data.category_ids).await?;
+
+        txn.commit().await?;
+
+        Ok(product)
+    }
+
+    /// Replaces the gallery of a product, published as a new revision of its live content.
+    /// Newly attached images are also added to any drafts waiting to be published.
+    pub async fn update_product_images(
+        db: &DbConn,
+        product: product::Model,
+        images: Vec<ProductImageInput>,
+    ) -> Result<product::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let live = Query::find_product_revision(&txn, product.id, product.revision_id)
             .await?
-            .ok_or(DbErr::Custom("Cannot find product.".to_owned()))
-            .map(Into::into)?;
+            .ok_or(DbErr::RecordNotFound(
+                "Cannot find product revision.".to_owned(),
+            ))?;
+        let mut revision_input = live.to_input();
+        revision_input.images = images;
+
+        let revision = Self::create_product_revision(
+            &txn,
+            &revision_input,
+            Some(product.id),
+            Some(live.detail.id),
+            REVISION_STATUS_PUBLISHED,
+        )
+        .await?;
+
+        Self::record_product_event(
+            &txn,
+            product.id,
+            Some(revision.id),
+            PRODUCT_EVENT_REVISION_PUBLISHED,
+        )
+        .await?;
+
+        let gallery = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(revision.id))
+            .order_by_asc(product_image::Column::Position)
+            .all(&txn)
+            .await?;
+
+        // Images attached by this update are added to open drafts too, so publishing one
+        // doesn't drop them. The images a draft already has and their order are left alone.
+        let attached: Vec<&product_image::Model> = gallery
+            .iter()
+            .filter(|image| {
+                !live
+                    .images
+                    .iter()
+                    .any(|live_image| live_image.hash_id == image.hash_id)
+            })
+            .collect();
+        let drafts = ProductDetail::find()
+            .filter(product_detail::Column::ProductId.eq(product.id))
+            .filter(
+                product_detail::Column::Status
+                    .is_in([REVISION_STATUS_DRAFT, REVISION_STATUS_SCHEDULED]),
+            )
+            .all(&txn)
+            .await?;
+        for draft in drafts {
+            let draft_images = ProductImage::find()
+                .filter(product_image::Column::ProductId.eq(draft.id))
+                .all(&txn)
+                .await?;
+            let mut position = draft_images
+                .iter()
+                .map(|draft_image| draft_image.position)
+                .max()
+                .unwrap_or(-1);
+
+            for image in &attached {
+                if draft_images
+                    .iter()
+                    .any(|draft_image| draft_image.hash_id == image.hash_id)
+                {
+                    continue;
+                }
+
+                position += 1;
+                ProductImage::insert(product_image::ActiveModel {
+                    hash_id: Set(image.hash_id),
+                    product_id: Set(draft.id),
+                    position: Set(position),
+                    alt_text: Set(image.alt_text.to_owned()),
+                    ..Default::default()
+                })
+                .exec(&txn)
+                .await?;
+            }
+        }
+
+        let mut product: product::ActiveModel = product.into();
+        product.revision_id = Set(revision.id);
+        let product = product.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(product)
+    }
+
+    /// Adds an upload to the media library along with the resized copies stored for it
+    pub async fn create_media(
+        db: &DbConn,
+        media_input: MediaInput,
+    ) -> Result<(media::Model, Vec<image_variant::Model>), DbErr> {
+        let txn = db.begin().await?;
 
-        product::ActiveModel {
-            id: post.id,
+        let media = media::ActiveModel {
+            hash_id: Set(media_input.hash_id),
+            account_id: Set(media_input.account_id),
+            file_name: Set(media_input.file_name),
+            content_type: Set(media_input.content_type),
+            width: Set(media_input.width),
+            height: Set(media_input.height),
+            size: Set(media_input.size),
+            creation_date: Set(Utc::now().naive_utc()),
             ..Default::default()
         }
-        .update(db)
+        .insert(&txn)
+        .await?;
+
+        let mut variants = vec![];
+        for variant in media_input.variants {
+            let model = image_variant::ActiveModel {
+                hash_id: Set(media_input.hash_id),
+                name: Set(variant.name),
+                key: Set(variant.key),
+                width: Set(variant.width),
+                height: Set(variant.height),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            variants.push(model);
+        }
+
+        txn.commit().await?;
+
+        Ok((media, variants))
+    }
+
+    /// Removes images from the media library once their files are deleted
+    pub async fn delete_media(db: &DbConn, hash_ids: Vec<Uuid>) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+
+        ImageVariant::delete_many()
+            .filter(image_variant::Column::HashId.is_in(hash_ids.to_owned()))
+            .exec(&txn)
+            .await?;
+        Media::delete_many()
+            .filter(media::Column::HashId.is_in(hash_ids))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_product_categories<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+        category_ids: &[i32],
+    ) -> Result<(), DbErr> {
+        ProductCategory::delete_many()
+            .filter(product_category::Column::ProductId.eq(product_id))
+            .exec(db)
+            .await?;
+
+        for category_id in category_ids {
+            ProductCategory::insert(product_category::ActiveModel {
+                product_id: Set(product_id),
+                category_id: Set(*category_id),
+                ..Default::default()
+            })
+            .exec(db)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Saves a revision based on the live one without putting it live
+    pub async fn create_product_draft(
+        db: &DbConn,
+        product: &product::Model,
+        new_data: RevisionInput,
+    ) -> Result<product_detail::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let revision = Self::create_product_revision(
+            &txn,
+            &new_data,
+            Some(product.id),
+            Some(product.revision_id),
+            REVISION_STATUS_DRAFT,
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(revision)
+    }
+
+    /// Puts a draft live, re-parenting it onto the current revision so the history stays linear
+    pub async fn publish_product_draft(
+        db: &DbConn,
+        product: product::Model,
+        draft: product_detail::Model,
+    ) -> Result<product::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let parent_id = product.revision_id;
+        let mut draft: product_detail::ActiveModel = draft.into();
+        draft.parent_id = Set(Some(parent_id));
+        draft.status = Set(REVISION_STATUS_PUBLISHED.to_owned());
+        draft.scheduled_date = Set(None);
+        let draft = draft.update(&txn).await?;
+
+        Self::record_product_event(
+            &txn,
+            product.id,
+            Some(draft.id),
+            PRODUCT_EVENT_REVISION_PUBLISHED,
+        )
+        .await?;
+
+        let mut product: product::ActiveModel = product.into();
+        product.revision_id = Set(draft.id);
+        let product = product.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(product)
+    }
+
+    /// Holds a draft until the given time, when the publishing task puts it live
+    pub async fn schedule_product_draft(
+        db: &DbConn,
+        draft: product_detail::Model,
+        scheduled_date: NaiveDateTime,
+    ) -> Result<product_detail::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let product_id = draft.product_id;
+        let mut draft: product_detail::ActiveModel = draft.into();
+        draft.status = Set(REVISION_STATUS_SCHEDULED.to_owned());
+        draft.scheduled_date = Set(Some(scheduled_date));
+        let draft = draft.update(&txn).await?;
+
+        if let Some(product_id) = product_id {
+            Self::record_product_event(
+                &txn,
+                product_id,
+                Some(draft.id),
+                PRODUCT_EVENT_REVISION_SCHEDULED,
+            )
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(draft)
+    }
+
+    async fn record_product_event<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+        revision_id: Option<i32>,
+        kind: &str,
+    ) -> Result<product_event::Model, DbErr> {
+        ProductEvent::insert(product_event::ActiveModel {
+            product_id: Set(product_id),
+            revision_id: Set(revision_id),
+            kind: Set(kind.to_owned()),
+            creation_date: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
         .await
     }
 
+    /// Rolls back by copying an earlier revision into a new one, leaving sold revisions untouched
+    pub async fn rollback_product(
+        db: &DbConn,
+        product: product::Model,
+        revision_id: i32,
+    ) -> Result<product::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let previous = Query::find_product_revision(&txn, product.id, revision_id)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find product revision.".to_owned()))?;
+
+        let revision = Self::create_product_revision(
+            &txn,
+            &previous.to_input(),
+            Some(product.id),
+            Some(product.revision_id),
+            REVISION_STATUS_PUBLISHED,
+        )
+        .await?;
+
+        Self::record_product_event(
+            &txn,
+            product.id,
+            Some(revision.id),
+            PRODUCT_EVENT_REVISION_ROLLED_BACK,
+        )
+        .await?;
+
+        let mut product: product::ActiveModel = product.into();
+        product.revision_id = Set(revision.id);
+        let product = product.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(product)
+    }
+
     pub async fn delete_product(db: &DbConn, id: i32) -> Result<DeleteResult, DbErr> {
-        let post: product::ActiveModel = Product::find_by_id(id)
-            .one(db)
+        let txn = db.begin().await?;
+
+        let product = Product::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find product.".to_owned()))?;
+        let revision_ids = Query::find_product_revision_ids(&txn, product.id).await?;
+
+        ProductEvent::delete_many()
+            .filter(product_event::Column::ProductId.eq(product.id))
+            .exec(&txn)
+            .await?;
+
+        ProductAttribute::delete_many()
+            .filter(product_attribute::Column::ProductId.is_in(revision_ids.to_owned()))
+            .exec(&txn)
+            .await?;
+
+        ProductImage::delete_many()
+            .filter(product_image::Column::ProductId.is_in(revision_ids.to_owned()))
+            .exec(&txn)
+            .await?;
+
+        ProductCategory::delete_many()
+            .filter(product_category::Column::ProductId.eq(product.id))
+            .exec(&txn)
+            .await?;
+
+        RestockSubscription::delete_many()
+            .filter(restock_subscription::Column::ProductId.eq(product.id))
+            .exec(&txn)
+            .await?;
+
+        Stock::delete_many()
+            .filter(stock::Column::ProductId.eq(product.id))
+            .exec(&txn)
+            .await?;
+
+        let variant_ids: Vec<i32> = ProductVariant::find()
+            .select_only()
+            .column(product_variant::Column::Id)
+            .filter(product_variant::Column::ProductId.eq(product.id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        ProductVariantOption::delete_many()
+            .filter(product_variant_option::Column::VariantId.is_in(variant_ids.to_owned()))
+            .exec(&txn)
+            .await?;
+
+        ProductVariantImage::delete_many()
+            .filter(product_variant_image::Column::VariantId.is_in(variant_ids.to_owned()))
+            .exec(&txn)
+            .await?;
+
+        ProductVariant::delete_many()
+            .filter(product_variant::Column::Id.is_in(variant_ids))
+            .exec(&txn)
+            .await?;
+
+        let result = Product::delete_by_id(product.id).exec(&txn).await?;
+
+        ProductDetail::delete_many()
+            .filter(product_detail::Column::Id.is_in(revision_ids))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Creates a variant for every combination of options offered by the attributes on the
+    /// live revision, skipping combinations that already have one
+    pub async fn generate_product_variants(
+        db: &DbConn,
+        product: &product::Model,
+    ) -> Result<Vec<product_variant::Model>, DbErr> {
+        let txn = db.begin().await?;
+
+        let values: Vec<(i32, String)> = ProductAttribute::find()
+            .select_only()
+            .column(product_attribute::Column::AttributeId)
+            .column(product_attribute::Column::Content)
+            .filter(product_attribute::Column::ProductId.eq(product.revision_id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        let mut attribute_ids: Vec<i32> = values
+            .iter()
+            .map(|(attribute_id, _)| *attribute_id)
+            .collect();
+        attribute_ids.sort_unstable();
+        attribute_ids.dedup();
+
+        // Only the options the revision picked for each attribute make up its variants
+        let options: Vec<attribute_option::Model> = AttributeOption::find()
+            .filter(attribute_option::Column::AttributeId.is_in(attribute_ids.to_owned()))
+            .order_by_asc(attribute_option::Column::Position)
+            .order_by_asc(attribute_option::Column::Id)
+            .all(&txn)
+            .await?
+            .into_iter()
+            .filter(|option| {
+                values.iter().any(|(attribute_id, content)| {
+                    *attribute_id == option.attribute_id && is_option_selected(option, content)
+                })
+            })
+            .collect();
+
+        let mut combinations: Vec<Vec<&attribute_option::Model>> = vec![vec![]];
+        for attribute_id in attribute_ids {
+            let attribute_options: Vec<&attribute_option::Model> = options
+                .iter()
+                .filter(|option| option.attribute_id == attribute_id)
+                .collect();
+
+            if attribute_options.is_empty() {
+                continue;
+            }
+
+            let mut next_combinations = vec![];
+            for combination in &combinations {
+                for option in &attribute_options {
+                    let mut next_combination = combination.to_owned();
+                    next_combination.push(*option);
+                    next_combinations.push(next_combination);
+                }
+            }
+            combinations = next_combinations;
+        }
+
+        let existing_variant_ids: Vec<i32> = ProductVariant::find()
+            .select_only()
+            .column(product_variant::Column::Id)
+            .filter(product_variant::Column::ProductId.eq(product.id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        let existing_options = ProductVariantOption::find()
+            .filter(
+                product_variant_option::Column::VariantId.is_in(existing_variant_ids.to_owned()),
+            )
+            .all(&txn)
+            .await?;
+
+        let existing_combinations: Vec<Vec<i32>> = existing_variant_ids
+            .iter()
+            .map(|variant_id| {
+                let mut option_ids: Vec<i32> = existing_options
+                    .iter()
+                    .filter(|option| option.variant_id == *variant_id)
+                    .map(|option| option.attribute_option_id)
+                    .collect();
+                option_ids.sort_unstable();
+                option_ids
+            })
+            .collect();
+
+        let mut variants = vec![];
+        for combination in combinations {
+            if combination.is_empty() {
+                continue;
+            }
+
+            let mut option_ids: Vec<i32> = combination.iter().map(|option| option.id).collect();
+            option_ids.sort_unstable();
+            if existing_combinations.contains(&option_ids) {
+                continue;
+            }
+
+            let option_labels = combination
+                .iter()
+                .map(|option| {
+                    option
+                        .label
+                        .trim()
+                        .to_ascii_lowercase()
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("-");
+
+            // SKUs are unique across products, so suffix the generated one until it's free
+            let base_sku = format!("{}-{}", product.short_url, option_labels);
+            let mut sku = base_sku.to_owned();
+            let mut suffix = 2;
+            while ProductVariant::find()
+                .filter(product_variant::Column::Sku.eq(&sku))
+                .count(&txn)
+                .await?
+                > 0
+            {
+                sku = format!("{}-{}", base_sku, suffix);
+                suffix += 1;
+            }
+
+            let variant = ProductVariant::insert(product_variant::ActiveModel {
+                product_id: Set(product.id),
+                sku: Set(sku),
+                price: Set(None),
+                active: Set(true),
+                ..Default::default()
+            })
+            .exec_with_returning(&txn)
+            .await?;
+
+            for option in combination {
+                ProductVariantOption::insert(product_variant_option::ActiveModel {
+                    variant_id: Set(variant.id),
+                    attribute_id: Set(option.attribute_id),
+                    attribute_option_id: Set(option.id),
+                    ..Default::default()
+                })
+                .exec(&txn)
+                .await?;
+            }
+
+            variants.push(variant);
+        }
+
+        txn.commit().await?;
+
+        Ok(variants)
+    }
+
+    pub async fn update_product_variant(
+        db: &DbConn,
+        variant: product_variant::Model,
+        new_data: VariantInput,
+    ) -> Result<product_variant::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let mut variant: product_variant::ActiveModel = variant.into();
+        variant.sku = Set(new_data.sku);
+        variant.price = Set(new_data.price);
+        variant.active = Set(new_data.active);
+        let variant = variant.update(&txn).await?;
+
+        ProductVariantImage::delete_many()
+            .filter(product_variant_image::Column::VariantId.eq(variant.id))
+            .exec(&txn)
+            .await?;
+
+        for (position, image_id) in new_data.image_ids.iter().enumerate() {
+            ProductVariantImage::insert(product_variant_image::ActiveModel {
+                variant_id: Set(variant.id),
+                hash_id: Set(*image_id),
+                position: Set(position as i32),
+                ..Default::default()
+            })
+            .exec(&txn)
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(variant)
+    }
+
+    pub async fn create_attribute(
+        db: &DbConn,
+        input: AttributeInput,
+    ) -> Result<(attribute::Model, Vec<attribute_option::Model>), DbErr> {
+        let txn = db.begin().await?;
+
+        let attribute = Attribute::insert(attribute::ActiveModel {
+            kind: Set(input.kind.to_owned()),
+            label: Set(input.label.to_owned()),
+            ..Default::default()
+        })
+        .exec_with_returning(&txn)
+        .await?;
+
+        let options = Self::save_attribute_options(&txn, attribute.id, &input).await?;
+
+        txn.commit().await?;
+
+        Ok((attribute, options))
+    }
+
+    /// Renames an attribute, changes its kind and brings its options in line with
+    /// `input`. Options left out are removed, callers must ensure no variant selects them.
+    pub async fn update_attribute(
+        db: &DbConn,
+        attribute: attribute::Model,
+        input: AttributeInput,
+    ) -> Result<(attribute::Model, Vec<attribute_option::Model>), DbErr> {
+        let txn = db.begin().await?;
+
+        let mut active_attribute: attribute::ActiveModel = attribute.into();
+        active_attribute.kind = Set(input.kind.to_owned());
+        active_attribute.label = Set(input.label.to_owned());
+        let attribute = active_attribute.update(&txn).await?;
+
+        let kept_ids: Vec<i32> = input
+            .options
+            .iter()
+            .filter_map(|option| option.id)
+            .collect();
+        AttributeOption::delete_many()
+            .filter(attribute_option::Column::AttributeId.eq(attribute.id))
+            .filter(attribute_option::Column::Id.is_not_in(kept_ids))
+            .exec(&txn)
+            .await?;
+
+        let options = Self::save_attribute_options(&txn, attribute.id, &input).await?;
+
+        txn.commit().await?;
+
+        Ok((attribute, options))
+    }
+
+    async fn save_attribute_options<C: ConnectionTrait>(
+        db: &C,
+        attribute_id: i32,
+        input: &AttributeInput,
+    ) -> Result<Vec<attribute_option::Model>, DbErr> {
+        let mut options = vec![];
+
+        for (position, option) in input.options.iter().enumerate() {
+            let saved = match option.id {
+                Some(option_id) => {
+                    let existing = AttributeOption::find_by_id(option_id)
+                        .filter(attribute_option::Column::AttributeId.eq(attribute_id))
+                        .one(db)
+                        .await?
+                        .ok_or(DbErr::RecordNotFound(
+                            "Cannot find attribute option.".to_owned(),
+                        ))?;
+
+                    let mut existing: attribute_option::ActiveModel = existing.into();
+                    existing.label = Set(option.label.to_owned());
+                    existing.content = Set(option.content.to_owned());
+                    existing.position = Set(position as i32);
+                    existing.update(db).await?
+                }
+                None => {
+                    AttributeOption::insert(attribute_option::ActiveModel {
+                        attribute_id: Set(attribute_id),
+                        label: Set(option.label.to_owned()),
+                        content: Set(option.content.to_owned()),
+                        position: Set(position as i32),
+                        ..Default::default()
+                    })
+                    .exec_with_returning(db)
+                    .await?
+                }
+            };
+            options.push(saved);
+        }
+
+        Ok(options)
+    }
+
+    /// Deletes an attribute and its options, callers must ensure nothing references it
+    pub async fn delete_attribute(
+        db: &DbConn,
+        attribute: attribute::Model,
+    ) -> Result<DeleteResult, DbErr> {
+        let txn = db.begin().await?;
+
+        AttributeOption::delete_many()
+            .filter(attribute_option::Column::AttributeId.eq(attribute.id))
+            .exec(&txn)
+            .await?;
+
+        let result = Attribute::delete_by_id(attribute.id).exec(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(result)
+    }
+
+    pub async fn create_category(
+        db: &DbConn,
+        input: CategoryInput,
+    ) -> Result<category::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let category = Category::insert(category::ActiveModel {
+            label: Set(input.label),
+            parent_id: Set(input.parent_id),
+            position: Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(&txn)
+        .await?;
+
+        Self::order_category_siblings(&txn, input.parent_id, Some((category.id, input.position)))
+            .await?;
+
+        let category = Category::find_by_id(category.id)
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Cannot find category.".to_owned()))?;
+
+        txn.commit().await?;
+
+        Ok(category)
+    }
+
+    /// Renames and moves a category along with its subtree. Callers must ensure the new
+    /// parent is not the category itself or one of its descendants.
+    pub async fn update_category(
+        db: &DbConn,
+        category: category::Model,
+        input: CategoryInput,
+    ) -> Result<category::Model, DbErr> {
+        let txn = db.begin().await?;
+        let previous_parent_id = category.parent_id;
+
+        let mut active_category: category::ActiveModel = category.into();
+        active_category.label = Set(input.label);
+        active_category.parent_id = Set(input.parent_id);
+        let category = active_category.update(&txn).await?;
+
+        Self::order_category_siblings(&txn, input.parent_id, Some((category.id, input.position)))
+            .await?;
+        if previous_parent_id != input.parent_id {
+            Self::order_category_siblings(&txn, previous_parent_id, None).await?;
+        }
+
+        let category = Category::find_by_id(category.id)
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Cannot find category.".to_owned()))?;
+
+        txn.commit().await?;
+
+        Ok(category)
+    }
+
+    /// Deletes a category, first handing its children and products to `reassign_to`
+    /// when given. Callers must ensure the target lies outside the deleted subtree.
+    pub async fn delete_category(
+        db: &DbConn,
+        category: category::Model,
+        reassign_to: Option<i32>,
+    ) -> Result<DeleteResult, DbErr> {
+        let txn = db.begin().await?;
+
+        if let Some(target_id) = reassign_to {
+            let offset = Category::find()
+                .filter(category::Column::ParentId.eq(target_id))
+                .count(&txn)
+                .await?;
+            let children = Category::find()
+                .filter(category::Column::ParentId.eq(category.id))
+                .order_by_asc(category::Column::Position)
+                .order_by_asc(category::Column::Label)
+                .order_by_asc(category::Column::Id)
+                .all(&txn)
+                .await?;
+            for (position, child) in children.into_iter().enumerate() {
+                let mut child: category::ActiveModel = child.into();
+                child.parent_id = Set(Some(target_id));
+                child.position = Set((offset as usize + position) as i32);
+                child.update(&txn).await?;
+            }
+
+            Product::update_many()
+                .col_expr(product::Column::CategoryId, Expr::value(target_id))
+                .filter(product::Column::CategoryId.eq(category.id))
+                .exec(&txn)
+                .await?;
+
+            // Products already in the target keep their single membership there
+            ProductCategory::delete_many()
+                .filter(product_category::Column::CategoryId.eq(category.id))
+                .filter(
+                    product_category::Column::ProductId.in_subquery(
+                        sea_query::Query::select()
+                            .column(product_category::Column::ProductId)
+                            .from(ProductCategory)
+                            .and_where(product_category::Column::CategoryId.eq(target_id))
+                            .to_owned(),
+                    ),
+                )
+                .exec(&txn)
+                .await?;
+
+            ProductCategory::update_many()
+                .col_expr(product_category::Column::CategoryId, Expr::value(target_id))
+                .filter(product_category::Column::CategoryId.eq(category.id))
+                .exec(&txn)
+                .await?;
+        }
+
+        let result = Category::delete_by_id(category.id).exec(&txn).await?;
+        Self::order_category_siblings(&txn, category.parent_id, None).await?;
+
+        txn.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Renumbers the children of `parent_id` from zero, optionally placing `moved`
+    /// (a category id and its requested position) among them
+    async fn order_category_siblings<C: ConnectionTrait>(
+        db: &C,
+        parent_id: Option<i32>,
+        moved: Option<(i32, Option<i32>)>,
+    ) -> Result<(), DbErr> {
+        let parent_condition = match parent_id {
+            Some(parent_id) => category::Column::ParentId.eq(parent_id),
+            None => category::Column::ParentId.is_null(),
+        };
+
+        let mut query = Category::find().filter(parent_condition);
+        if let Some((moved_id, _)) = moved {
+            query = query.filter(category::Column::Id.ne(moved_id));
+        }
+        let mut category_ids: Vec<i32> = query
+            .order_by_asc(category::Column::Position)
+            .order_by_asc(category::Column::Label)
+            .order_by_asc(category::Column::Id)
+            .all(db)
             .await?
-            .ok_or(DbErr::Custom("Cannot find product.".to_owned()))
-            .map(Into::into)?;
+            .into_iter()
+            .map(|category| category.id)
+            .collect();
+
+        if let Some((moved_id, position)) = moved {
+            let index = position
+                .map(|position| (position.max(0) as usize).min(category_ids.len()))
+                .unwrap_or(category_ids.len());
+            category_ids.insert(index, moved_id);
+        }
 
-        post.delete(db).await
+        for (position, category_id) in category_ids.into_iter().enumerate() {
+            Category::update_many()
+                .col_expr(category::Column::Position, Expr::value(position as i32))
+                .filter(category::Column::Id.eq(category_id))
+                .exec(db)
+                .await?;
+        }
+
+        Ok(())
     }
 
     pub async fn delete_all_products(db: &DbConn) -> Result<DeleteResult, DbErr> {
         Product::delete_many().exec(db).await
     }
+
+    /// Moves stock between locations as a pair of ledger entries sharing a transfer id, as
+    /// long as the source has the amount available. Its stock stays locked until the entries
+    /// are written so concurrent transfers can't both take the same units.
+    pub async fn transfer_stock(
+        db: &DbConn,
+        transfer_id: Uuid,
+        product_id: i32,
+        variant_id: Option<i32>,
+        from_location_id: i32,
+        to_location_id: i32,
+        amount: i32,
+    ) -> Result<TransferOutcome, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let mut source_stock = Stock::find()
+            .filter(stock::Column::ProductId.eq(product_id))
+            .filter(stock::Column::LocationId.eq(from_location_id));
+        if let Some(variant_id) = variant_id {
+            source_stock = source_stock.filter(stock::Column::VariantId.eq(variant_id));
+        }
+        source_stock.lock_exclusive().all(&txn).await?;
+
+        // Counted after taking the lock, so entries written by a transfer that held it first
+        // are included
+        let on_hand = Query::find_location_stock_levels(&txn, product_id, variant_id)
+            .await?
+            .get(&from_location_id)
+            .copied()
+            .unwrap_or(0);
+        let reserved = Query::find_location_reserved_quantities(&txn, product_id, variant_id)
+            .await?
+            .get(&from_location_id)
+            .copied()
+            .unwrap_or(0);
+        let available = on_hand - reserved;
+        if available < amount as i64 {
+            return Ok(TransferOutcome::Insufficient(available));
+        }
+
+        let outgoing = Stock::insert(stock::ActiveModel {
+            product_id: Set(product_id),
+            location_id: Set(from_location_id),
+            variant_id: Set(variant_id),
+            amount: Set(-amount),
+            kind: Set(STOCK_KIND_TRANSFER.to_owned()),
+            transfer_id: Set(Some(transfer_id)),
+            addition_date: Set(now),
+            ..Default::default()
+        })
+        .exec_with_returning(&txn)
+        .await?;
+
+        let incoming = Stock::insert(stock::ActiveModel {
+            product_id: Set(product_id),
+            location_id: Set(to_location_id),
+            variant_id: Set(variant_id),
+            amount: Set(amount),
+            kind: Set(STOCK_KIND_TRANSFER.to_owned()),
+            transfer_id: Set(Some(transfer_id)),
+            addition_date: Set(now),
+            ..Default::default()
+        })
+        .exec_with_returning(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(TransferOutcome::Transferred(outgoing, incoming))
+    }
+
+    /// Issues a reset token for an account, voiding any it was issued before
+    pub async fn create_password_reset_token(
+        db: &DbConn,
+        account_id: i32,
+        token_hash: String,
+    ) -> Result<password_reset_token::Model, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        Self::invalidate_password_reset_tokens(&txn, account_id, now).await?;
+
+        let token = password_reset_token::ActiveModel {
+            account_id: Set(account_id),
+            token_hash: Set(token_hash),
+            creation_date: Set(now),
+            expiry_date: Set(now + Duration::minutes(PASSWORD_RESET_TOKEN_LIFETIME_MINUTES)),
+            used_date: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(token)
+    }
+
+    /// Sets a new password with a reset token, which is used up along with every other
+    /// outstanding token of the account. Returns `None` if the token is unknown, expired
+    /// or already used.
+    pub async fn reset_password(
+        db: &DbConn,
+        token_hash: &str,
+        password_hash: String,
+    ) -> Result<Option<account::Model>, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let valid_token = Condition::all()
+            .add(password_reset_token::Column::TokenHash.eq(token_hash))
+            .add(password_reset_token::Column::UsedDate.is_null())
+            .add(password_reset_token::Column::ExpiryDate.gt(now));
+        let Some(token) = PasswordResetToken::find()
+            .filter(valid_token.to_owned())
+            .one(&txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Only one of two concurrent resets with the same token gets to use it
+        let used = PasswordResetToken::update_many()
+            .col_expr(password_reset_token::Column::UsedDate, Expr::value(now))
+            .filter(valid_token)
+            .exec(&txn)
+            .await?;
+        if used.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        let account = account::ActiveModel {
+            id: Set(token.account_id),
+            password: Set(password_hash),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await?;
+
+        Self::invalidate_password_reset_tokens(&txn, account.id, now).await?;
+        // Whoever knew the old password is signed out everywhere
+        Self::revoke_sessions(&txn, account_session::Column::AccountId.eq(account.id), now).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(account))
+    }
+
+    async fn invalidate_password_reset_tokens<C: ConnectionTrait>(
+        db: &C,
+        account_id: i32,
+        now: NaiveDateTime,
+    ) -> Result<(), DbErr> {
+        PasswordResetToken::update_many()
+            .col_expr(password_reset_token::Column::UsedDate, Expr::value(now))
+            .filter(password_reset_token::Column::AccountId.eq(account_id))
+            .filter(password_reset_token::Column::UsedDate.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Starts a session for an account along with its first refresh token
+    pub async fn create_session(
+        db: &DbConn,
+        account_id: i32,
+        user_agent: Option<String>,
+        token_hash: String,
+        lifetime: Duration,
+    ) -> Result<account_session::Model, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let session = account_session::ActiveModel {
+            account_id: Set(account_id),
+            user_agent: Set(user_agent),
+            creation_date: Set(now),
+            last_used_date: Set(now),
+            expiry_date: Set(now + lifetime),
+            revoked_date: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        refresh_token::ActiveModel {
+            session_id: Set(session.id),
+            token_hash: Set(token_hash),
+            creation_date: Set(now),
+            expiry_date: Set(now + lifetime),
+            used_date: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(session)
+    }
+
+    /// Exchanges a refresh token for a new one, extending its session. A token that
+    /// was already exchanged means it has leaked, so its whole session is revoked.
+    pub async fn rotate_refresh_token(
+        db: &DbConn,
+        token_hash: &str,
+        new_token_hash: String,
+        lifetime: Duration,
+    ) -> Result<RefreshOutcome, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let Some((token, Some(session))) = RefreshToken::find()
+            .find_also_related(AccountSession)
+            .filter(refresh_token::Column::TokenHash.eq(token_hash))
+            .one(&txn)
+            .await?
+        else {
+            return Ok(RefreshOutcome::Invalid);
+        };
+
+        match refresh_token_rejection(&token, &session, now) {
+            Some(RefreshOutcome::Reused) => {
+                Self::revoke_sessions(&txn, account_session::Column::Id.eq(session.id), now)
+                    .await?;
+                txn.commit().await?;
+                return Ok(RefreshOutcome::Reused);
+            }
+            Some(outcome) => return Ok(outcome),
+            None => {}
+        }
+
+        // Only one of two concurrent refreshes with the same token gets to use it
+        let used = RefreshToken::update_many()
+            .col_expr(refresh_token::Column::UsedDate, Expr::value(now))
+            .filter(refresh_token::Column::Id.eq(token.id))
+            .filter(refresh_token::Column::UsedDate.is_null())
+            .exec(&txn)
+            .await?;
+        if used.rows_affected == 0 {
+            Self::revoke_sessions(&txn, account_session::Column::Id.eq(session.id), now).await?;
+            txn.commit().await?;
+            return Ok(RefreshOutcome::Reused);
+        }
+
+        refresh_token::ActiveModel {
+            session_id: Set(session.id),
+            token_hash: Set(new_token_hash),
+            creation_date: Set(now),
+            expiry_date: Set(now + lifetime),
+            used_date: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let session = account_session::ActiveModel {
+            id: Set(session.id),
+            last_used_date: Set(now),
+            expiry_date: Set(now + lifetime),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(RefreshOutcome::Rotated(session))
+    }
+
+    /// Ends one session of an account, returning whether there was an active one to end
+    pub async fn revoke_session(
+        db: &DbConn,
+        account_id: i32,
+        session_id: i32,
+    ) -> Result<bool, DbErr> {
+        let revoked = Self::revoke_sessions(
+            db,
+            Condition::all()
+                .add(account_session::Column::Id.eq(session_id))
+                .add(account_session::Column::AccountId.eq(account_id)),
+            Utc::now().naive_utc(),
+        )
+        .await?;
+
+        Ok(revoked > 0)
+    }
+
+    /// Ends every session of an account, returning how many were active
+    pub async fn revoke_account_sessions(db: &DbConn, account_id: i32) -> Result<u64, DbErr> {
+        Self::revoke_sessions(
+            db,
+            account_session::Column::AccountId.eq(account_id),
+            Utc::now().naive_utc(),
+        )
+        .await
+    }
+
+    async fn revoke_sessions<C: ConnectionTrait, F: sea_query::IntoCondition>(
+        db: &C,
+        filter: F,
+        now: NaiveDateTime,
+    ) -> Result<u64, DbErr> {
+        let revoked = AccountSession::update_many()
+            .col_expr(account_session::Column::RevokedDate, Expr::value(now))
+            .filter(filter)
+            .filter(account_session::Column::RevokedDate.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(revoked.rows_affected)
+    }
+
+    /// Notes that a verification email was just sent, for throttling resends
+    pub async fn record_verification_email(
+        db: &DbConn,
+        account_id: i32,
+    ) -> Result<account::Model, DbErr> {
+        account::ActiveModel {
+            id: Set(account_id),
+            verification_email_date: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn verify_email(db: &DbConn, account_id: i32) -> Result<account::Model, DbErr> {
+        account::ActiveModel {
+            id: Set(account_id),
+            email_verified: Set(true),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_role(db: &DbConn, role: RoleInput) -> Result<RoleDetails, DbErr> {
+        let txn = db.begin().await?;
+
+        let created = role::ActiveModel {
+            name: Set(role.name),
+            description: Set(role.description),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        Self::insert_role_permissions(&txn, created.id, &role.permissions).await?;
+
+        txn.commit().await?;
+
+        Ok(RoleDetails {
+            role: created,
+            permissions: role.permissions,
+        })
+    }
+
+    /// Replaces the name, description and permissions of a role
+    pub async fn update_role(
+        db: &DbConn,
+        role_id: i32,
+        role: RoleInput,
+    ) -> Result<RoleDetails, DbErr> {
+        let txn = db.begin().await?;
+
+        let updated = role::ActiveModel {
+            id: Set(role_id),
+            name: Set(role.name),
+            description: Set(role.description),
+        }
+        .update(&txn)
+        .await?;
+
+        RolePermission::delete_many()
+            .filter(role_permission::Column::RoleId.eq(role_id))
+            .exec(&txn)
+            .await?;
+        Self::insert_role_permissions(&txn, role_id, &role.permissions).await?;
+
+        txn.commit().await?;
+
+        Ok(RoleDetails {
+            role: updated,
+            permissions: role.permissions,
+        })
+    }
+
+    /// Deletes a role, taking it away from every account it was assigned to
+    pub async fn delete_role(db: &DbConn, role_id: i32) -> Result<DeleteResult, DbErr> {
+        Role::delete_by_id(role_id).exec(db).await
+    }
+
+    /// Replaces the roles assigned to an account
+    pub async fn set_account_roles(
+        db: &DbConn,
+        account_id: i32,
+        role_ids: Vec<i32>,
+    ) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+
+        AccountRole::delete_many()
+            .filter(account_role::Column::AccountId.eq(account_id))
+            .exec(&txn)
+            .await?;
+
+        if !role_ids.is_empty() {
+            AccountRole::insert_many(role_ids.into_iter().map(|role_id| {
+                account_role::ActiveModel {
+                    account_id: Set(account_id),
+                    role_id: Set(role_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn insert_role_permissions<C: ConnectionTrait>(
+        db: &C,
+        role_id: i32,
+        permissions: &[Permission],
+    ) -> Result<(), DbErr> {
+        if permissions.is_empty() {
+            return Ok(());
+        }
+
+        RolePermission::insert_many(permissions.iter().map(|permission| {
+            role_permission::ActiveModel {
+                role_id: Set(role_id),
+                permission: Set(permission.as_str().to_owned()),
+                ..Default::default()
+            }
+        }))
+        .exec(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Locks the throttle of an email address or IP address for the rest of the
+    /// transaction, creating it first when there is none yet
+    async fn lock_login_throttle<C: ConnectionTrait>(
+        db: &C,
+        kind: &str,
+        key: &str,
+        now: NaiveDateTime,
+    ) -> Result<login_throttle::Model, DbErr> {
+        let inserted = LoginThrottle::insert(login_throttle::ActiveModel {
+            kind: Set(kind.to_owned()),
+            key: Set(key.to_owned()),
+            failure_count: Set(0),
+            last_failure_date: Set(now),
+            locked_until: Set(None),
+            ..Default::default()
+        })
+        .on_conflict(
+            sea_query::OnConflict::columns([
+                login_throttle::Column::Kind,
+                login_throttle::Column::Key,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec(db)
+        .await;
+        match inserted {
+            Ok(_) | Err(DbErr::RecordNotInserted) => {}
+            Err(e) => return Err(e),
+        }
+
+        LoginThrottle::find()
+            .filter(login_throttle::Column::Kind.eq(kind))
+            .filter(login_throttle::Column::Key.eq(key))
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(format!("Login throttle {}", key)))
+    }
+
+    /// Counts a login attempt as failed against every throttle before its password is
+    /// checked, so concurrent attempts can't all slip past the limits. Nothing is counted
+    /// when a throttle makes the attempt wait, the date it may be retried is returned instead.
+    pub async fn reserve_login_attempt(
+        db: &DbConn,
+        throttles: &[(&str, &str, LoginThrottlePolicy)],
+    ) -> Result<Option<NaiveDateTime>, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let mut locked = vec![];
+        let mut retry_date = None;
+        for (kind, key, policy) in throttles {
+            let throttle = Self::lock_login_throttle(&txn, kind, key, now).await?;
+            retry_date = retry_date.max(login_retry_date(&throttle, policy, now));
+            locked.push(throttle);
+        }
+
+        if retry_date.is_some() {
+            txn.rollback().await?;
+            return Ok(retry_date);
+        }
+
+        for throttle in locked {
+            let failure_count = login_failure_count(&throttle, now) + 1;
+            let mut updated: login_throttle::ActiveModel = throttle.into();
+            updated.failure_count = Set(failure_count);
+            updated.last_failure_date = Set(now);
+            updated.update(&txn).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(None)
+    }
+
+    /// Settles a reserved attempt that turned out to fail, locking the email address or
+    /// IP address out once it has failed too often. Returns the throttle if it was locked.
+    pub async fn record_login_failure(
+        db: &DbConn,
+        kind: &str,
+        key: &str,
+        policy: &LoginThrottlePolicy,
+    ) -> Result<Option<login_throttle::Model>, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let throttle = Self::lock_login_throttle(&txn, kind, key, now).await?;
+        let locked = throttle.locked_until.is_some_and(|date| date > now);
+        if locked || throttle.failure_count < policy.lockout_failures {
+            txn.commit().await?;
+            return Ok(None);
+        }
+
+        let mut updated: login_throttle::ActiveModel = throttle.into();
+        updated.locked_until = Set(Some(now + Duration::minutes(LOGIN_LOCKOUT_MINUTES)));
+        let throttle = updated.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(throttle))
+    }
+
+    /// Gives back a reserved attempt that succeeded
+    pub async fn release_login_attempt(
+        db: &DbConn,
+        kind: &str,
+        key: &str,
+    ) -> Result<UpdateResult, DbErr> {
+        LoginThrottle::update_many()
+            .col_expr(
+                login_throttle::Column::FailureCount,
+                Expr::col(login_throttle::Column::FailureCount).sub(1),
+            )
+            .filter(login_throttle::Column::Kind.eq(kind))
+            .filter(login_throttle::Column::Key.eq(key))
+            .filter(login_throttle::Column::FailureCount.gt(0))
+            .exec(db)
+            .await
+    }
+
+    /// Removes the throttles whose failures have been forgotten and that aren't locked out
+    pub async fn delete_stale_login_throttles(db: &DbConn) -> Result<DeleteResult, DbErr> {
+        let now = Utc::now().naive_utc();
+
+        LoginThrottle::delete_many()
+            .filter(
+                login_throttle::Column::LastFailureDate
+                    .lte(now - Duration::minutes(LOGIN_FAILURE_WINDOW_MINUTES)),
+            )
+            .filter(
+                Condition::any()
+                    .add(login_throttle::Column::LockedUntil.is_null())
+                    .add(login_throttle::Column::LockedUntil.lte(now)),
+            )
+            .exec(db)
+            .await
+    }
+
+    /// Forgets the failed logins of an email address or IP address, lifting any lockout
+    pub async fn clear_login_failures(
+        db: &DbConn,
+        kind: &str,
+        key: &str,
+    ) -> Result<DeleteResult, DbErr> {
+        LoginThrottle::delete_many()
+            .filter(login_throttle::Column::Kind.eq(kind))
+            .filter(login_throttle::Column::Key.eq(key))
+            .exec(db)
+            .await
+    }
+
+    pub async fn create_login_event(
+        db: &DbConn,
+        event: login_event::ActiveModel,
+    ) -> Result<login_event::Model, DbErr> {
+        login_event::ActiveModel {
+            creation_date: Set(Utc::now().naive_utc()),
+            ..event
+        }
+        .insert(db)
+        .await
+    }
 }
@@ -0,0 +1,73 @@
+use ::entity::role;
+use std::collections::HashSet;
+
+/// The built-in role holding every permission, kept as it is so the store can't be
+/// left without anyone able to manage roles
+pub const ADMIN_ROLE_NAME: &str = "admin";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Permission {
+    /// Products, categories, attributes, revisions, variants and media
+    CatalogManage,
+    /// Stock levels, transfers and locations
+    InventoryManage,
+    OrdersView,
+    AccountsManage,
+    RolesManage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 5] = [
+        Permission::CatalogManage,
+        Permission::InventoryManage,
+        Permission::OrdersView,
+        Permission::AccountsManage,
+        Permission::RolesManage,
+    ];
+
+    pub fn parse(permission: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == permission)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::CatalogManage => "catalog:manage",
+            Permission::InventoryManage => "inventory:manage",
+            Permission::OrdersView => "orders:view",
+            Permission::AccountsManage => "accounts:manage",
+            Permission::RolesManage => "roles:manage",
+        }
+    }
+}
+
+/// Everything an account may do through the roles assigned to it
+#[derive(Debug, Clone, Default)]
+pub struct AccountPermissions(pub HashSet<Permission>);
+
+impl AccountPermissions {
+    pub fn has(&self, permission: Permission) -> bool {
+        self.0.contains(&permission)
+    }
+
+    /// Names of the granted permissions in the order of `Permission::ALL`
+    pub fn names(&self) -> Vec<&'static str> {
+        Permission::ALL
+            .into_iter()
+            .filter(|permission| self.has(*permission))
+            .map(|permission| permission.as_str())
+            .collect()
+    }
+}
+
+pub struct RoleInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<Permission>,
+}
+
+pub struct RoleDetails {
+    pub role: role::Model,
+    pub permissions: Vec<Permission>,
+}
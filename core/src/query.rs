@@ -1,5 +1,41 @@
-use ::entity::{product, product::Entity as Product};
-use sea_orm::*;
+use crate::catalog::{
+    build_category_tree, price_facets, product_filter_condition, product_option_query,
+    visible_product_condition, AttributeFacet, CategoryNode, FacetGroup, ListedProduct,
+    MediaDetails, MediaPage, ProductDetails, ProductFacets, ProductFilters, ProductPage,
+    ProductRevision, ProductSort, VariantDetails, PRICE_FACET_BOUNDARIES,
+    REVISION_STATUS_SCHEDULED,
+};
+use crate::inventory::{
+    reserved_order_item_condition, total_available, LocationInventory, ProductInventory,
+    CANCELLED_ORDER_STATUS, OPEN_ORDER_STATUSES, SALES_VELOCITY_DAYS,
+};
+use crate::permission::{AccountPermissions, Permission, RoleDetails};
+use crate::search::{search_count_sql, search_sql, search_values, SearchPage, SearchResult};
+use ::entity::{
+    account, account::Entity as Account, account_role, account_role::Entity as AccountRole,
+    account_session, account_session::Entity as AccountSession, attribute,
+    attribute::Entity as Attribute, attribute_option, attribute_option::Entity as AttributeOption,
+    category, category::Entity as Category, image_variant, image_variant::Entity as ImageVariant,
+    location, location::Entity as Location, login_event, login_event::Entity as LoginEvent,
+    login_throttle, login_throttle::Entity as LoginThrottle, media, media::Entity as Media, order,
+    order::Entity as Order, order_item, order_item::Entity as OrderItem, password_reset_token,
+    password_reset_token::Entity as PasswordResetToken, product, product::Entity as Product,
+    product_attribute, product_attribute::Entity as ProductAttribute, product_category,
+    product_category::Entity as ProductCategory, product_detail,
+    product_detail::Entity as ProductDetail, product_event, product_event::Entity as ProductEvent,
+    product_image, product_image::Entity as ProductImage, product_variant,
+    product_variant::Entity as ProductVariant, product_variant_image,
+    product_variant_image::Entity as ProductVariantImage, product_variant_option,
+    product_variant_option::Entity as ProductVariantOption, role, role::Entity as Role,
+    role_permission, role_permission::Entity as RolePermission, stock, stock::Entity as Stock,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    prelude::{Decimal, Uuid},
+    sea_query::{extension::postgres::PgExpr, Alias, Expr},
+    *,
+};
+use std::collections::{HashMap, HashSet};
 
 pub struct Query;
 
@@ -8,18 +44,1273 @@ impl Query {
         Product::find_by_id(id).one(db).await
     }
 
+    /// Looks a product up by numeric id, falling back to its short url
+    pub async fn find_product_by_id_or_short_url(
+        db: &DbConn,
+        id_or_short_url: &str,
+    ) -> Result<Option<product::Model>, DbErr> {
+        if let Ok(id) = id_or_short_url.parse::<i32>() {
+            if let Some(product) = Self::find_product_by_id(db, id).await? {
+                return Ok(Some(product));
+            }
+        }
+
+        Product::find()
+            .filter(product::Column::ShortUrl.eq(id_or_short_url))
+            .one(db)
+            .await
+    }
+
+    /// Ids of every revision saved for a product, drafts included
+    pub async fn find_product_revision_ids<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+    ) -> Result<Vec<i32>, DbErr> {
+        ProductDetail::find()
+            .select_only()
+            .column(product_detail::Column::Id)
+            .filter(product_detail::Column::ProductId.eq(product_id))
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    /// Revisions of a product, newest first
+    pub async fn find_product_revisions(
+        db: &DbConn,
+        product_id: i32,
+    ) -> Result<Vec<product_detail::Model>, DbErr> {
+        ProductDetail::find()
+            .filter(product_detail::Column::ProductId.eq(product_id))
+            .order_by_desc(product_detail::Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Scheduled revisions whose publish time has passed, with the products they belong to
+    pub async fn find_due_scheduled_revisions(
+        db: &DbConn,
+        now: NaiveDateTime,
+    ) -> Result<Vec<(product_detail::Model, Option<product::Model>)>, DbErr> {
+        ProductDetail::find()
+            .filter(product_detail::Column::Status.eq(REVISION_STATUS_SCHEDULED))
+            .filter(product_detail::Column::ScheduledDate.lte(now))
+            .order_by_asc(product_detail::Column::ScheduledDate)
+            .select_also(Product)
+            .join(
+                JoinType::LeftJoin,
+                product_detail::Relation::RevisionOf.def(),
+            )
+            .all(db)
+            .await
+    }
+
+    /// Publishing and status changes recorded for a product, newest first
+    pub async fn find_product_events(
+        db: &DbConn,
+        product_id: i32,
+    ) -> Result<Vec<product_event::Model>, DbErr> {
+        ProductEvent::find()
+            .filter(product_event::Column::ProductId.eq(product_id))
+            .order_by_desc(product_event::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_product_revision<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+        revision_id: i32,
+    ) -> Result<Option<ProductRevision>, DbErr> {
+        let detail = match ProductDetail::find_by_id(revision_id)
+            .filter(product_detail::Column::ProductId.eq(product_id))
+            .one(db)
+            .await?
+        {
+            Some(detail) => detail,
+            None => return Ok(None),
+        };
+
+        let attributes = ProductAttribute::find()
+            .filter(product_attribute::Column::ProductId.eq(detail.id))
+            .order_by_asc(product_attribute::Column::Id)
+            .all(db)
+            .await?;
+
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(detail.id))
+            .order_by_asc(product_image::Column::Position)
+            .order_by_asc(product_image::Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(Some(ProductRevision {
+            detail,
+            attributes,
+            images,
+        }))
+    }
+
+    pub async fn find_product_has_orders(
+        db: &DbConn,
+        product: &product::Model,
+    ) -> Result<bool, DbErr> {
+        let revision_ids = Self::find_product_revision_ids(db, product.id).await?;
+        let order_items = OrderItem::find()
+            .filter(order_item::Column::ProductId.is_in(revision_ids))
+            .count(db)
+            .await?;
+
+        Ok(order_items > 0)
+    }
+
+    /// Loads the live revision of a product along with its images, categories and attributes
+    pub async fn find_product_details(
+        db: &DbConn,
+        product: product::Model,
+    ) -> Result<ProductDetails, DbErr> {
+        let detail = ProductDetail::find_by_id(product.revision_id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(
+                "Cannot find product revision.".to_owned(),
+            ))?;
+
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(detail.id))
+            .order_by_asc(product_image::Column::Position)
+            .order_by_asc(product_image::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut categories = Category::find()
+            .join(
+                JoinType::InnerJoin,
+                category::Relation::ProductCategory.def(),
+            )
+            .filter(product_category::Column::ProductId.eq(product.id))
+            .order_by_asc(category::Column::Label)
+            .all(db)
+            .await?;
+        categories.sort_by_key(|category| Some(category.id) != product.category_id);
+
+        let product_attributes = ProductAttribute::find()
+            .filter(product_attribute::Column::ProductId.eq(detail.id))
+            .order_by_asc(product_attribute::Column::Id)
+            .find_also_related(Attribute)
+            .all(db)
+            .await?;
+
+        let attribute_options = AttributeOption::find()
+            .filter(
+                attribute_option::Column::AttributeId.is_in(
+                    product_attributes
+                        .iter()
+                        .map(|(product_attribute, _)| product_attribute.attribute_id)
+                        .collect::<Vec<_>>(),
+                ),
+            )
+            .order_by_asc(attribute_option::Column::Position)
+            .order_by_asc(attribute_option::Column::Id)
+            .all(db)
+            .await?;
+
+        let attributes = product_attributes
+            .into_iter()
+            .filter_map(|(product_attribute, attribute)| {
+                let attribute = attribute?;
+                let options = attribute_options
+                    .iter()
+                    .filter(|option| option.attribute_id == attribute.id)
+                    .cloned()
+                    .collect();
+                Some((product_attribute, attribute, options))
+            })
+            .collect();
+
+        let available = Self::find_product_availability(db, product.id).await?;
+        // Deactivated variants can no longer be bought, so shoppers shouldn't see them
+        let variants = Self::find_product_variants(db, product.id)
+            .await?
+            .into_iter()
+            .filter(|variant| variant.variant.active)
+            .collect();
+
+        Ok(ProductDetails {
+            product,
+            detail,
+            images,
+            categories,
+            attributes,
+            available,
+            variants,
+        })
+    }
+
+    pub async fn find_product_variant(
+        db: &DbConn,
+        product_id: i32,
+        variant_id: i32,
+    ) -> Result<Option<product_variant::Model>, DbErr> {
+        ProductVariant::find_by_id(variant_id)
+            .filter(product_variant::Column::ProductId.eq(product_id))
+            .one(db)
+            .await
+    }
+
+    /// Variants of a product with the option values that define them and their availability
+    pub async fn find_product_variants(
+        db: &DbConn,
+        product_id: i32,
+    ) -> Result<Vec<VariantDetails>, DbErr> {
+        let variants = ProductVariant::find()
+            .filter(product_variant::Column::ProductId.eq(product_id))
+            .order_by_asc(product_variant::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut variant_details = vec![];
+        for variant in variants {
+            let options = Self::find_variant_options(db, variant.id).await?;
+            let images = ProductVariantImage::find()
+                .filter(product_variant_image::Column::VariantId.eq(variant.id))
+                .order_by_asc(product_variant_image::Column::Position)
+                .order_by_asc(product_variant_image::Column::Id)
+                .all(db)
+                .await?;
+            let available = total_available(
+                &Self::find_product_location_inventory(db, product_id, Some(variant.id)).await?,
+            );
+
+            variant_details.push(VariantDetails {
+                variant,
+                options,
+                images,
+                available,
+            });
+        }
+
+        Ok(variant_details)
+    }
+
+    /// The attribute values chosen by a variant, in attribute order
+    pub async fn find_variant_options(
+        db: &DbConn,
+        variant_id: i32,
+    ) -> Result<Vec<(attribute::Model, attribute_option::Model)>, DbErr> {
+        let options = ProductVariantOption::find()
+            .filter(product_variant_option::Column::VariantId.eq(variant_id))
+            .order_by_asc(product_variant_option::Column::AttributeId)
+            .find_also_related(AttributeOption)
+            .all(db)
+            .await?;
+
+        let attributes = Attribute::find()
+            .filter(
+                attribute::Column::Id.is_in(
+                    options
+                        .iter()
+                        .map(|(option, _)| option.attribute_id)
+                        .collect::<Vec<_>>(),
+                ),
+            )
+            .all(db)
+            .await?;
+
+        Ok(options
+            .into_iter()
+            .filter_map(|(option, attribute_option)| {
+                let attribute = attributes
+                    .iter()
+                    .find(|attribute| attribute.id == option.attribute_id)?;
+                Some((attribute.to_owned(), attribute_option?))
+            })
+            .collect())
+    }
+
+    /// Ranked full-text search over the live revisions of visible products
+    pub async fn search_products(
+        db: &DbConn,
+        query: &str,
+        page: u64,
+        posts_per_page: u64,
+    ) -> Result<SearchPage, DbErr> {
+        let now = Utc::now().naive_utc();
+
+        let total_items = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &search_count_sql(),
+                search_values(query, now),
+            ))
+            .await?
+            .map(|row| row.try_get::<i64>("", "count"))
+            .transpose()?
+            .unwrap_or(0) as u64;
+
+        let results = SearchResult::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &search_sql(posts_per_page, (page - 1) * posts_per_page),
+            search_values(query, now),
+        ))
+        .all(db)
+        .await?;
+
+        let images = Self::find_primary_product_images(
+            db,
+            results.iter().map(|result| result.revision_id).collect(),
+        )
+        .await?;
+
+        Ok(SearchPage {
+            results: results
+                .into_iter()
+                .map(|result| {
+                    let image = images.get(&result.revision_id).copied();
+                    (result, image)
+                })
+                .collect(),
+            total_items,
+            total_pages: total_items.div_ceil(posts_per_page),
+        })
+    }
+
     pub async fn find_products_in_page(
         db: &DbConn,
         page: u64,
         posts_per_page: u64,
-    ) -> Result<(Vec<product::Model>, u64), DbErr> {
-        let paginator = Product::find()
-            .select()
+        filters: &ProductFilters,
+        sort: ProductSort,
+    ) -> Result<ProductPage, DbErr> {
+        let mut query = Product::find()
+            .select_only()
             .column(product::Column::Id)
             .column(product::Column::ShortUrl)
+            .column(product::Column::RevisionId)
+            .column(product::Column::CategoryId)
+            .column_as(category::Column::Label, "category_label")
+            .column(product_detail::Column::Name)
+            .column(product_detail::Column::Price)
+            .join(JoinType::InnerJoin, product::Relation::ProductDetail.def())
+            .join(JoinType::LeftJoin, product::Relation::Category.def());
+
+        let selected_options = if filters.option_ids.is_empty() {
+            HashMap::new()
+        } else {
+            Self::find_selected_options(db, &filters.option_ids).await?
+        };
+        query = query.filter(product_filter_condition(
+            filters,
+            &selected_options,
+            None,
+            Utc::now().naive_utc(),
+        ));
+
+        query = match sort {
+            ProductSort::Newest => query.order_by_desc(product::Column::Id),
+            ProductSort::Name => query.order_by_asc(product_detail::Column::Name),
+            ProductSort::PriceAsc => query.order_by_asc(product_detail::Column::Price),
+            ProductSort::PriceDesc => query.order_by_desc(product_detail::Column::Price),
+        };
+
+        let paginator = query
+            .order_by_asc(product::Column::Id)
+            .into_model::<ListedProduct>()
             .paginate(db, posts_per_page);
-        let num_pages = paginator.num_pages().await?;
+        let ItemsAndPagesNumber {
+            number_of_items,
+            number_of_pages,
+        } = paginator.num_items_and_pages().await?;
+        let products = paginator.fetch_page(page.max(1) - 1).await?;
+
+        let images = Self::find_primary_product_images(
+            db,
+            products.iter().map(|product| product.revision_id).collect(),
+        )
+        .await?;
+
+        Ok(ProductPage {
+            products: products
+                .into_iter()
+                .map(|product| {
+                    let image = images.get(&product.revision_id).copied();
+                    (product, image)
+                })
+                .collect(),
+            total_items: number_of_items,
+            total_pages: number_of_pages,
+        })
+    }
+
+    /// All attributes with their options in display order
+    pub async fn find_attributes(
+        db: &DbConn,
+    ) -> Result<Vec<(attribute::Model, Vec<attribute_option::Model>)>, DbErr> {
+        Attribute::find()
+            .find_with_related(AttributeOption)
+            .order_by_asc(attribute::Column::Id)
+            .order_by_asc(attribute_option::Column::Position)
+            .order_by_asc(attribute_option::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_attribute(
+        db: &DbConn,
+        id: i32,
+    ) -> Result<Option<(attribute::Model, Vec<attribute_option::Model>)>, DbErr> {
+        let Some(attribute) = Attribute::find_by_id(id).one(db).await? else {
+            return Ok(None);
+        };
+
+        let options = AttributeOption::find()
+            .filter(attribute_option::Column::AttributeId.eq(attribute.id))
+            .order_by_asc(attribute_option::Column::Position)
+            .order_by_asc(attribute_option::Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(Some((attribute, options)))
+    }
+
+    /// Counts the product attribute values and variant selections referencing an attribute
+    pub async fn find_attribute_usage(db: &DbConn, id: i32) -> Result<(u64, u64), DbErr> {
+        let product_attributes = ProductAttribute::find()
+            .filter(product_attribute::Column::AttributeId.eq(id))
+            .count(db)
+            .await?;
+        let variant_options = ProductVariantOption::find()
+            .filter(product_variant_option::Column::AttributeId.eq(id))
+            .count(db)
+            .await?;
+
+        Ok((product_attributes, variant_options))
+    }
+
+    /// Distinct contents product revisions hold for an attribute
+    pub async fn find_attribute_contents(db: &DbConn, id: i32) -> Result<Vec<String>, DbErr> {
+        ProductAttribute::find()
+            .select_only()
+            .column(product_attribute::Column::Content)
+            .distinct()
+            .filter(product_attribute::Column::AttributeId.eq(id))
+            .order_by_asc(product_attribute::Column::Content)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    /// Counts the variants selecting any of the given options
+    pub async fn find_attribute_option_usage(
+        db: &DbConn,
+        option_ids: Vec<i32>,
+    ) -> Result<u64, DbErr> {
+        ProductVariantOption::find()
+            .filter(product_variant_option::Column::AttributeOptionId.is_in(option_ids))
+            .count(db)
+            .await
+    }
+
+    /// Groups selected attribute options by the attribute they belong to
+    pub async fn find_selected_options(
+        db: &DbConn,
+        option_ids: &[i32],
+    ) -> Result<HashMap<i32, Vec<i32>>, DbErr> {
+        let options = AttributeOption::find()
+            .filter(attribute_option::Column::Id.is_in(option_ids.to_owned()))
+            .all(db)
+            .await?;
+
+        let mut selected_options: HashMap<i32, Vec<i32>> = HashMap::new();
+        for option in options {
+            selected_options
+                .entry(option.attribute_id)
+                .or_default()
+                .push(option.id);
+        }
+
+        Ok(selected_options)
+    }
+
+    /// Counts visible products per category, attribute option and price range. Each
+    /// group is counted with every filter applied except its own selection.
+    pub async fn find_product_facets(
+        db: &DbConn,
+        filters: &ProductFilters,
+    ) -> Result<ProductFacets, DbErr> {
+        let now = Utc::now().naive_utc();
+        let selected_options = Self::find_selected_options(db, &filters.option_ids).await?;
+
+        let category_counts: HashMap<i32, i64> = Product::find()
+            .select_only()
+            .column(product_category::Column::CategoryId)
+            .column_as(Expr::col((Product, product::Column::Id)).count(), "count")
+            .join(JoinType::InnerJoin, product::Relation::ProductDetail.def())
+            .join(
+                JoinType::InnerJoin,
+                product::Relation::ProductCategory.def(),
+            )
+            .filter(product_filter_condition(
+                filters,
+                &selected_options,
+                Some(FacetGroup::Category),
+                now,
+            ))
+            .group_by(product_category::Column::CategoryId)
+            .into_tuple::<(i32, i64)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let categories = Category::find()
+            .filter(category::Column::Id.is_in(category_counts.keys().copied().collect::<Vec<_>>()))
+            .order_by_asc(category::Column::Label)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|category| {
+                let count = category_counts.get(&category.id).copied().unwrap_or(0) as u64;
+                (category, count)
+            })
+            .collect();
+
+        // Options carried by any visible product are offered even when the other
+        // filters leave none of their products
+        let carried_options =
+            Self::count_products_per_option(db, visible_product_condition(now)).await?;
+        let option_counts = Self::count_products_per_option(
+            db,
+            product_filter_condition(filters, &selected_options, None, now),
+        )
+        .await?;
+        let mut selected_option_counts = HashMap::new();
+        for attribute_id in selected_options.keys() {
+            let counts = Self::count_products_per_option(
+                db,
+                product_filter_condition(
+                    filters,
+                    &selected_options,
+                    Some(FacetGroup::Attribute(*attribute_id)),
+                    now,
+                ),
+            )
+            .await?;
+            selected_option_counts.insert(*attribute_id, counts);
+        }
+
+        let attribute_options = Attribute::find()
+            .find_with_related(AttributeOption)
+            .order_by_asc(attribute::Column::Label)
+            .order_by_asc(attribute_option::Column::Position)
+            .order_by_asc(attribute_option::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut attributes = Vec::new();
+        for (attribute, options) in attribute_options {
+            let counts = selected_option_counts
+                .get(&attribute.id)
+                .unwrap_or(&option_counts);
+            let options: Vec<_> = options
+                .into_iter()
+                .filter(|option| carried_options.contains_key(&option.id))
+                .map(|option| {
+                    let count = counts.get(&option.id).copied().unwrap_or(0);
+                    (option, count)
+                })
+                .collect();
+            if options.is_empty() {
+                continue;
+            }
+
+            attributes.push(AttributeFacet { attribute, options });
+        }
+
+        let price_bucket = Expr::cust(&format!(
+            r#"width_bucket("product_detail"."price", ARRAY[{}]::numeric[])"#,
+            PRICE_FACET_BOUNDARIES
+                .map(|boundary| boundary.to_string())
+                .join(", ")
+        ));
+        let price_counts: Vec<(i32, i64)> = Product::find()
+            .select_only()
+            .column_as(price_bucket.to_owned(), "bucket")
+            .column_as(Expr::col((Product, product::Column::Id)).count(), "count")
+            .join(JoinType::InnerJoin, product::Relation::ProductDetail.def())
+            .filter(product_filter_condition(
+                filters,
+                &selected_options,
+                Some(FacetGroup::Price),
+                now,
+            ))
+            .filter(product_detail::Column::Price.gte(Decimal::ZERO))
+            .group_by(price_bucket)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        let mut prices = price_facets();
+        for (bucket, count) in price_counts {
+            if let Some(price_facet) = prices.get_mut(bucket as usize) {
+                price_facet.count = count as u64;
+            }
+        }
+
+        Ok(ProductFacets {
+            categories,
+            attributes,
+            prices,
+        })
+    }
+
+    /// Counts the products matching a condition per attribute option they carry
+    async fn count_products_per_option(
+        db: &DbConn,
+        condition: Condition,
+    ) -> Result<HashMap<i32, u64>, DbErr> {
+        let option_id = Expr::col((Alias::new("product_option"), Alias::new("option_id")));
+        let mut query = Product::find()
+            .select_only()
+            .column_as(option_id.to_owned(), "option_id")
+            .column_as(Expr::col((Product, product::Column::Id)).count(), "count")
+            .join(JoinType::InnerJoin, product::Relation::ProductDetail.def())
+            .filter(condition)
+            .group_by(option_id);
+        QueryTrait::query(&mut query).join_subquery(
+            JoinType::InnerJoin,
+            product_option_query(),
+            Alias::new("product_option"),
+            Expr::col((Alias::new("product_option"), Alias::new("product_id")))
+                .equals((Product, product::Column::Id)),
+        );
+
+        let counts: Vec<(i32, i64)> = query.into_tuple().all(db).await?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(option_id, count)| (option_id, count as u64))
+            .collect())
+    }
+
+    /// Counts the product revisions and variants showing an uploaded image
+    pub async fn find_image_usage(db: &DbConn, hash_id: Uuid) -> Result<u64, DbErr> {
+        let product_images = ProductImage::find()
+            .filter(product_image::Column::HashId.eq(hash_id))
+            .count(db)
+            .await?;
+        let variant_images = ProductVariantImage::find()
+            .filter(product_variant_image::Column::HashId.eq(hash_id))
+            .count(db)
+            .await?;
+
+        Ok(product_images + variant_images)
+    }
+
+    /// How many product revisions and variants show each image
+    pub async fn find_image_usage_counts(
+        db: &DbConn,
+        hash_ids: Vec<Uuid>,
+    ) -> Result<HashMap<Uuid, u64>, DbErr> {
+        let product_images: Vec<(Uuid, i64)> = ProductImage::find()
+            .select_only()
+            .column(product_image::Column::HashId)
+            .column_as(product_image::Column::Id.count(), "count")
+            .filter(product_image::Column::HashId.is_in(hash_ids.to_owned()))
+            .group_by(product_image::Column::HashId)
+            .into_tuple()
+            .all(db)
+            .await?;
+        let variant_images: Vec<(Uuid, i64)> = ProductVariantImage::find()
+            .select_only()
+            .column(product_variant_image::Column::HashId)
+            .column_as(product_variant_image::Column::Id.count(), "count")
+            .filter(product_variant_image::Column::HashId.is_in(hash_ids))
+            .group_by(product_variant_image::Column::HashId)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        let mut usage: HashMap<Uuid, u64> = HashMap::new();
+        for (hash_id, count) in product_images.into_iter().chain(variant_images) {
+            *usage.entry(hash_id).or_default() += count as u64;
+        }
+
+        Ok(usage)
+    }
+
+    /// Uploads newest first, optionally only those whose file name contains `search`
+    pub async fn find_media_in_page(
+        db: &DbConn,
+        page: u64,
+        media_per_page: u64,
+        search: Option<&str>,
+    ) -> Result<MediaPage, DbErr> {
+        let mut query = Media::find().find_also_related(Account);
 
-        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+        if let Some(search) = search {
+            let escaped = search
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            query = query.filter(
+                Expr::col((Media, media::Column::FileName)).ilike(format!("%{}%", escaped)),
+            );
+        }
+
+        let paginator = query
+            .order_by_desc(media::Column::CreationDate)
+            .order_by_desc(media::Column::Id)
+            .paginate(db, media_per_page);
+        let ItemsAndPagesNumber {
+            number_of_items,
+            number_of_pages,
+        } = paginator.num_items_and_pages().await?;
+        let media = paginator.fetch_page(page.max(1) - 1).await?;
+
+        let hash_ids = media
+            .iter()
+            .map(|(media, _)| media.hash_id)
+            .collect::<Vec<_>>();
+        let mut variants = Self::find_image_variants(db, hash_ids.to_owned()).await?;
+        let usage = Self::find_image_usage_counts(db, hash_ids).await?;
+
+        Ok(MediaPage {
+            media: media
+                .into_iter()
+                .map(|(media, uploader)| MediaDetails {
+                    variants: variants.remove(&media.hash_id).unwrap_or_default(),
+                    usage: usage.get(&media.hash_id).copied().unwrap_or_default(),
+                    media,
+                    uploader,
+                })
+                .collect(),
+            total_items: number_of_items,
+            total_pages: number_of_pages,
+        })
+    }
+
+    /// Every image shown in a product revision or variant gallery
+    pub async fn find_referenced_image_ids(db: &DbConn) -> Result<HashSet<Uuid>, DbErr> {
+        let product_images: Vec<Uuid> = ProductImage::find()
+            .select_only()
+            .column(product_image::Column::HashId)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await?;
+        let variant_images: Vec<Uuid> = ProductVariantImage::find()
+            .select_only()
+            .column(product_variant_image::Column::HashId)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        Ok(product_images.into_iter().chain(variant_images).collect())
+    }
+
+    /// Resized copies of each image, smallest first
+    pub async fn find_image_variants(
+        db: &DbConn,
+        hash_ids: Vec<Uuid>,
+    ) -> Result<HashMap<Uuid, Vec<image_variant::Model>>, DbErr> {
+        let variants = ImageVariant::find()
+            .filter(image_variant::Column::HashId.is_in(hash_ids))
+            .order_by_asc(image_variant::Column::Width)
+            .order_by_asc(image_variant::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut image_variants: HashMap<Uuid, Vec<image_variant::Model>> = HashMap::new();
+        for variant in variants {
+            image_variants
+                .entry(variant.hash_id)
+                .or_default()
+                .push(variant);
+        }
+
+        Ok(image_variants)
+    }
+
+    /// First image of each revision by position, keyed by revision id
+    pub async fn find_primary_product_images(
+        db: &DbConn,
+        revision_ids: Vec<i32>,
+    ) -> Result<HashMap<i32, Uuid>, DbErr> {
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.is_in(revision_ids))
+            .order_by_asc(product_image::Column::Position)
+            .order_by_asc(product_image::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut primary_images = HashMap::new();
+        for image in images {
+            primary_images
+                .entry(image.product_id)
+                .or_insert(image.hash_id);
+        }
+
+        Ok(primary_images)
+    }
+
+    pub async fn find_category_by_id(
+        db: &DbConn,
+        id: i32,
+    ) -> Result<Option<category::Model>, DbErr> {
+        Category::find_by_id(id).one(db).await
+    }
+
+    pub async fn find_category_tree(db: &DbConn) -> Result<Vec<CategoryNode>, DbErr> {
+        Ok(build_category_tree(Category::find().all(db).await?))
+    }
+
+    /// Ancestors of a category from the root down, ending with the category itself
+    pub async fn find_category_breadcrumbs(
+        db: &DbConn,
+        category_id: i32,
+    ) -> Result<Vec<category::Model>, DbErr> {
+        let categories: HashMap<i32, category::Model> = Category::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|category| (category.id, category))
+            .collect();
+
+        let mut breadcrumbs: Vec<category::Model> = vec![];
+        let mut next_id = Some(category_id);
+        while let Some(category) = next_id.and_then(|id| categories.get(&id)) {
+            if breadcrumbs.iter().any(|crumb| crumb.id == category.id) {
+                break;
+            }
+            breadcrumbs.push(category.to_owned());
+            next_id = category.parent_id;
+        }
+        breadcrumbs.reverse();
+
+        Ok(breadcrumbs)
+    }
+
+    pub async fn find_category_child_count(db: &DbConn, category_id: i32) -> Result<u64, DbErr> {
+        Category::find()
+            .filter(category::Column::ParentId.eq(category_id))
+            .count(db)
+            .await
+    }
+
+    pub async fn find_category_product_count(db: &DbConn, category_id: i32) -> Result<u64, DbErr> {
+        ProductCategory::find()
+            .filter(product_category::Column::CategoryId.eq(category_id))
+            .count(db)
+            .await
+    }
+
+    /// The category along with every category nested beneath it
+    pub async fn find_category_tree_ids(db: &DbConn, category_id: i32) -> Result<Vec<i32>, DbErr> {
+        let categories = Category::find().all(db).await?;
+        let mut category_ids = vec![category_id];
+        let mut index = 0;
+
+        while index < category_ids.len() {
+            let parent_id = category_ids[index];
+            category_ids.extend(
+                categories
+                    .iter()
+                    .filter(|category| category.parent_id == Some(parent_id))
+                    .filter(|category| !category_ids.contains(&category.id))
+                    .map(|category| category.id)
+                    .collect::<Vec<_>>(),
+            );
+            index += 1;
+        }
+
+        Ok(category_ids)
+    }
+
+    pub async fn find_stock_levels(db: &DbConn) -> Result<HashMap<i32, i64>, DbErr> {
+        let stock_levels: Vec<(i32, Option<i64>)> = Stock::find()
+            .select_only()
+            .column(stock::Column::ProductId)
+            .column_as(stock::Column::Amount.sum(), "amount")
+            .group_by(stock::Column::ProductId)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        Ok(stock_levels
+            .into_iter()
+            .map(|(product_id, amount)| (product_id, amount.unwrap_or(0)))
+            .collect())
+    }
+
+    /// Quantities on orders that have not been fulfilled yet, keyed by product id
+    pub async fn find_reserved_quantities(db: &DbConn) -> Result<HashMap<i32, i64>, DbErr> {
+        Self::find_ordered_quantities(
+            db,
+            Condition::all().add(order::Column::Status.is_in(OPEN_ORDER_STATUSES)),
+        )
+        .await
+    }
+
+    /// Quantities sold since the provided date, keyed by product id
+    pub async fn find_sold_quantities(
+        db: &DbConn,
+        since: NaiveDateTime,
+    ) -> Result<HashMap<i32, i64>, DbErr> {
+        Self::find_ordered_quantities(
+            db,
+            Condition::all()
+                .add(order::Column::Status.ne(CANCELLED_ORDER_STATUS))
+                .add(order::Column::CreationDate.gte(since)),
+        )
+        .await
+    }
+
+    async fn find_ordered_quantities(
+        db: &DbConn,
+        condition: Condition,
+    ) -> Result<HashMap<i32, i64>, DbErr> {
+        let quantities: Vec<(i32, Option<i64>)> = OrderItem::find()
+            .select_only()
+            .column(product::Column::Id)
+            .column_as(order_item::Column::Qty.sum(), "qty")
+            .join(JoinType::InnerJoin, order_item::Relation::Order.def())
+            .join(
+                JoinType::InnerJoin,
+                order_item::Relation::ProductDetail.def(),
+            )
+            .join(
+                JoinType::InnerJoin,
+                product_detail::Relation::RevisionOf.def(),
+            )
+            .filter(condition)
+            .group_by(product::Column::Id)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        Ok(quantities
+            .into_iter()
+            .map(|(product_id, qty)| (product_id, qty.unwrap_or(0)))
+            .collect())
+    }
+
+    pub async fn find_inventory_report(db: &DbConn) -> Result<Vec<ProductInventory>, DbErr> {
+        let products = Product::find()
+            .find_also_related(ProductDetail)
+            .order_by_asc(product::Column::Id)
+            .all(db)
+            .await?;
+        let stock_levels = Self::find_stock_levels(db).await?;
+        let reserved_quantities = Self::find_reserved_quantities(db).await?;
+        let sold_quantities = Self::find_sold_quantities(
+            db,
+            (Utc::now() - Duration::days(SALES_VELOCITY_DAYS)).naive_utc(),
+        )
+        .await?;
+
+        Ok(products
+            .into_iter()
+            .map(|(product, product_detail)| {
+                let on_hand = stock_levels.get(&product.id).copied().unwrap_or(0);
+                let reserved = reserved_quantities.get(&product.id).copied().unwrap_or(0);
+                let sold = sold_quantities.get(&product.id).copied().unwrap_or(0);
+
+                ProductInventory {
+                    product_id: product.id,
+                    short_url: product.short_url,
+                    name: product_detail.map(|detail| detail.name).unwrap_or_default(),
+                    low_stock_threshold: product.low_stock_threshold,
+                    on_hand,
+                    reserved,
+                    backordered: (reserved - on_hand).max(0),
+                    sales_velocity: sold as f64 / SALES_VELOCITY_DAYS as f64,
+                }
+            })
+            .collect())
+    }
+
+    pub async fn find_locations(db: &DbConn) -> Result<Vec<location::Model>, DbErr> {
+        Location::find()
+            .order_by_asc(location::Column::Priority)
+            .order_by_asc(location::Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Stock on hand for a product at each location, keyed by location id
+    pub async fn find_location_stock_levels<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+        variant_id: Option<i32>,
+    ) -> Result<HashMap<i32, i64>, DbErr> {
+        let mut query = Stock::find()
+            .select_only()
+            .column(stock::Column::LocationId)
+            .column_as(stock::Column::Amount.sum(), "amount")
+            .filter(stock::Column::ProductId.eq(product_id));
+
+        if let Some(variant_id) = variant_id {
+            query = query.filter(stock::Column::VariantId.eq(variant_id));
+        }
+
+        let stock_levels: Vec<(i32, Option<i64>)> = query
+            .group_by(stock::Column::LocationId)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        Ok(stock_levels
+            .into_iter()
+            .map(|(location_id, amount)| (location_id, amount.unwrap_or(0)))
+            .collect())
+    }
+
+    /// Quantities of a product on unfulfilled orders, keyed by the location picked to fulfill them
+    pub async fn find_location_reserved_quantities<C: ConnectionTrait>(
+        db: &C,
+        product_id: i32,
+        variant_id: Option<i32>,
+    ) -> Result<HashMap<i32, i64>, DbErr> {
+        let mut query = OrderItem::find()
+            .select_only()
+            .column(order_item::Column::LocationId)
+            .column_as(order_item::Column::Qty.sum(), "qty")
+            .join(JoinType::InnerJoin, order_item::Relation::Order.def())
+            .join(
+                JoinType::InnerJoin,
+                order_item::Relation::ProductDetail.def(),
+            )
+            .join(
+                JoinType::InnerJoin,
+                product_detail::Relation::RevisionOf.def(),
+            )
+            .filter(product::Column::Id.eq(product_id))
+            .filter(reserved_order_item_condition());
+
+        if let Some(variant_id) = variant_id {
+            query = query.filter(order_item::Column::VariantId.eq(variant_id));
+        }
+
+        let quantities: Vec<(i32, Option<i64>)> = query
+            .group_by(order_item::Column::LocationId)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        Ok(quantities
+            .into_iter()
+            .map(|(location_id, qty)| (location_id, qty.unwrap_or(0)))
+            .collect())
+    }
+
+    /// Inventory of a product, or one of its variants, at every location in order of
+    /// fulfillment priority
+    pub async fn find_product_location_inventory(
+        db: &DbConn,
+        product_id: i32,
+        variant_id: Option<i32>,
+    ) -> Result<Vec<LocationInventory>, DbErr> {
+        let locations = Self::find_locations(db).await?;
+        let stock_levels = Self::find_location_stock_levels(db, product_id, variant_id).await?;
+        let reserved_quantities =
+            Self::find_location_reserved_quantities(db, product_id, variant_id).await?;
+
+        Ok(locations
+            .into_iter()
+            .map(|location| {
+                let on_hand = stock_levels.get(&location.id).copied().unwrap_or(0);
+                let reserved = reserved_quantities.get(&location.id).copied().unwrap_or(0);
+
+                LocationInventory::new(location, on_hand, reserved)
+            })
+            .collect())
+    }
+
+    /// Stock across every location that is not already promised to an open order
+    pub async fn find_product_availability(db: &DbConn, product_id: i32) -> Result<i64, DbErr> {
+        let inventory = Self::find_product_location_inventory(db, product_id, None).await?;
+
+        Ok(total_available(&inventory))
+    }
+
+    /// Picks the highest priority location able to fill the whole quantity, falling back to
+    /// the highest priority location overall so the line can be backordered from there
+    pub async fn find_fulfillment_location(
+        db: &DbConn,
+        product_id: i32,
+        variant_id: Option<i32>,
+        qty: i32,
+    ) -> Result<Option<i32>, DbErr> {
+        let inventory = Self::find_product_location_inventory(db, product_id, variant_id).await?;
+
+        Ok(inventory
+            .iter()
+            .find(|location| location.available >= qty as i64)
+            .or(inventory.first())
+            .map(|location| location.location_id))
+    }
+
+    /// The account's most recently issued password reset token
+    pub async fn find_latest_password_reset_token(
+        db: &DbConn,
+        account_id: i32,
+    ) -> Result<Option<password_reset_token::Model>, DbErr> {
+        PasswordResetToken::find()
+            .filter(password_reset_token::Column::AccountId.eq(account_id))
+            .order_by_desc(password_reset_token::Column::CreationDate)
+            .one(db)
+            .await
+    }
+
+    /// A session that is neither revoked nor expired
+    pub async fn find_active_session(
+        db: &DbConn,
+        session_id: i32,
+    ) -> Result<Option<account_session::Model>, DbErr> {
+        AccountSession::find_by_id(session_id)
+            .filter(account_session::Column::RevokedDate.is_null())
+            .filter(account_session::Column::ExpiryDate.gt(Utc::now().naive_utc()))
+            .one(db)
+            .await
+    }
+
+    /// The active sessions of an account, most recently used first
+    pub async fn find_account_sessions(
+        db: &DbConn,
+        account_id: i32,
+    ) -> Result<Vec<account_session::Model>, DbErr> {
+        AccountSession::find()
+            .filter(account_session::Column::AccountId.eq(account_id))
+            .filter(account_session::Column::RevokedDate.is_null())
+            .filter(account_session::Column::ExpiryDate.gt(Utc::now().naive_utc()))
+            .order_by_desc(account_session::Column::LastUsedDate)
+            .all(db)
+            .await
     }
+
+    /// Every role with its permissions, by name
+    pub async fn find_roles(db: &DbConn) -> Result<Vec<RoleDetails>, DbErr> {
+        let roles = Role::find()
+            .find_with_related(RolePermission)
+            .order_by_asc(role::Column::Name)
+            .all(db)
+            .await?;
+
+        Ok(roles
+            .into_iter()
+            .map(|(role, permissions)| role_details(role, permissions))
+            .collect())
+    }
+
+    pub async fn find_role(db: &DbConn, role_id: i32) -> Result<Option<RoleDetails>, DbErr> {
+        let Some(role) = Role::find_by_id(role_id).one(db).await? else {
+            return Ok(None);
+        };
+        let permissions = role.find_related(RolePermission).all(db).await?;
+
+        Ok(Some(role_details(role, permissions)))
+    }
+
+    pub async fn find_role_by_name(db: &DbConn, name: &str) -> Result<Option<role::Model>, DbErr> {
+        Role::find()
+            .filter(role::Column::Name.eq(name))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_account_roles(
+        db: &DbConn,
+        account_id: i32,
+    ) -> Result<Vec<role::Model>, DbErr> {
+        Role::find()
+            .inner_join(AccountRole)
+            .filter(account_role::Column::AccountId.eq(account_id))
+            .order_by_asc(role::Column::Name)
+            .all(db)
+            .await
+    }
+
+    /// Ids of the accounts a role is assigned to
+    pub async fn find_role_account_ids(db: &DbConn, role_id: i32) -> Result<Vec<i32>, DbErr> {
+        AccountRole::find()
+            .select_only()
+            .column(account_role::Column::AccountId)
+            .filter(account_role::Column::RoleId.eq(role_id))
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    /// What an account may do through all of its roles together
+    pub async fn find_account_permissions(
+        db: &DbConn,
+        account_id: i32,
+    ) -> Result<AccountPermissions, DbErr> {
+        let permissions = RolePermission::find()
+            .join(JoinType::InnerJoin, role_permission::Relation::Role.def())
+            .join(JoinType::InnerJoin, role::Relation::AccountRole.def())
+            .filter(account_role::Column::AccountId.eq(account_id))
+            .all(db)
+            .await?;
+
+        Ok(AccountPermissions(
+            permissions
+                .iter()
+                .filter_map(|permission| Permission::parse(&permission.permission))
+                .collect(),
+        ))
+    }
+
+    /// Email addresses and IP addresses currently locked out, soonest lifted first
+    pub async fn find_login_lockouts(db: &DbConn) -> Result<Vec<login_throttle::Model>, DbErr> {
+        LoginThrottle::find()
+            .filter(login_throttle::Column::LockedUntil.gt(Utc::now().naive_utc()))
+            .order_by_asc(login_throttle::Column::LockedUntil)
+            .all(db)
+            .await
+    }
+
+    /// The latest lockouts and unlocks, newest first
+    pub async fn find_login_events(
+        db: &DbConn,
+        limit: u64,
+    ) -> Result<Vec<login_event::Model>, DbErr> {
+        LoginEvent::find()
+            .order_by_desc(login_event::Column::CreationDate)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+
+    /// Orders placed by an account, including those checked out as a guest with its email
+    /// address, newest first
+    pub async fn find_account_orders(
+        db: &DbConn,
+        account: &account::Model,
+    ) -> Result<Vec<(order::Model, Vec<order_item::Model>)>, DbErr> {
+        Order::find()
+            .filter(
+                Condition::any()
+                    .add(order::Column::AccountId.eq(account.id))
+                    .add(order::Column::Email.eq(account.email.to_owned())),
+            )
+            .order_by_desc(order::Column::CreationDate)
+            .order_by_desc(order::Column::Id)
+            .find_with_related(OrderItem)
+            .all(db)
+            .await
+    }
+}
+
+/// Permissions no longer known to the code are left out
+fn role_details(role: role::Model, permissions: Vec<role_permission::Model>) -> RoleDetails {
+    let mut permissions = permissions
+        .iter()
+        .filter_map(|permission| Permission::parse(&permission.permission))
+        .collect::<Vec<_>>();
+    permissions.sort();
+
+    RoleDetails { role, permissions }
 }
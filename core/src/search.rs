@@ -0,0 +1,117 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use sea_orm::{prelude::Uuid, FromQueryResult, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::PRODUCT_STATUS_ACTIVE;
+
+pub const MAX_SEARCH_TERMS: usize = 8;
+
+/// Rebuilds the search document of a revision from its fields and attribute values.
+/// Name and UPC weigh the most, then description, then attribute content.
+pub(crate) const REFRESH_SEARCH_VECTOR_SQL: &str = r#"UPDATE "product_detail" SET "search_vector" =
+    setweight(to_tsvector('english', coalesce("product_detail"."name", '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce("product_detail"."upc", '')), 'A') ||
+    setweight(to_tsvector('english', coalesce("product_detail"."description", '')), 'B') ||
+    setweight(to_tsvector('english', coalesce((
+        SELECT string_agg("product_attribute"."content", ' ')
+        FROM "product_attribute"
+        WHERE "product_attribute"."product_id" = "product_detail"."id"
+    ), '')), 'C')
+WHERE "product_detail"."id" = $1"#;
+
+/// Live revisions of visible products matching the query. Expects the tsquery, the active
+/// status and the current time as its first three parameters.
+const SEARCH_FROM_SQL: &str = r#"FROM "product"
+INNER JOIN "product_detail" ON "product_detail"."id" = "product"."revision_id",
+to_tsquery('english', $1) AS "search"("query")
+WHERE "product_detail"."search_vector" @@ "search"."query"
+AND "product"."status" = $2
+AND ("product"."publish_date" IS NULL OR "product"."publish_date" <= $3)
+AND ("product"."unpublish_date" IS NULL OR "product"."unpublish_date" > $3)"#;
+
+#[derive(Debug, Clone, Deserialize, Serialize, FromQueryResult)]
+pub struct SearchResult {
+    pub id: i32,
+    pub short_url: String,
+    pub revision_id: i32,
+    pub name: String,
+    pub price: Decimal,
+    pub rank: f32,
+    /// HTML-escaped matching excerpt with the matched terms wrapped in `<mark>` tags
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchPage {
+    pub results: Vec<(SearchResult, Option<Uuid>)>,
+    pub total_items: u64,
+    pub total_pages: u64,
+}
+
+/// Turns free text into a tsquery where every term matches as a prefix, so partially typed
+/// words still find results
+pub fn build_prefix_query(search: &str) -> Option<String> {
+    let terms = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect::<Vec<_>>();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" & "))
+    }
+}
+
+pub(crate) fn search_sql(limit: u64, offset: u64) -> String {
+    format!(
+        r#"SELECT "product"."id", "product"."short_url", "product"."revision_id",
+"product_detail"."name", "product_detail"."price",
+ts_rank("product_detail"."search_vector", "search"."query") AS "rank",
+ts_headline('english', {}, "search"."query",
+    'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=24, MinWords=8') AS "snippet"
+{}
+ORDER BY "rank" DESC, "product"."id" DESC
+LIMIT {} OFFSET {}"#,
+        escape_html_sql(r#""product_detail"."name" || ' ' || "product_detail"."description""#),
+        SEARCH_FROM_SQL,
+        limit,
+        offset
+    )
+}
+
+/// HTML-escapes a text expression so the `<mark>` tags added by ts_headline are the only
+/// markup in a snippet
+fn escape_html_sql(expr: &str) -> String {
+    [
+        ("&", "&amp;"),
+        ("<", "&lt;"),
+        (">", "&gt;"),
+        ("\"", "&quot;"),
+        ("'", "&#39;"),
+    ]
+    .iter()
+    .fold(expr.to_owned(), |expr, (from, to)| {
+        format!(
+            "replace({}, '{}', '{}')",
+            expr,
+            from.replace('\'', "''"),
+            to
+        )
+    })
+}
+
+pub(crate) fn search_count_sql() -> String {
+    format!(r#"SELECT COUNT(*) AS "count" {}"#, SEARCH_FROM_SQL)
+}
+
+pub(crate) fn search_values(query: &str, now: NaiveDateTime) -> Vec<Value> {
+    vec![
+        query.to_owned().into(),
+        PRODUCT_STATUS_ACTIVE.to_owned().into(),
+        now.into(),
+    ]
+}
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: NaiveDateTime,
+}
+
+/// Where uploaded files are kept and how the public reaches them
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Stores a file under `key`, replacing any file already there
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError>;
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Every stored file
+    async fn list(&self) -> Result<Vec<StoredObject>, StorageError>;
+
+    fn public_url(&self, key: &str) -> String;
+}
@@ -0,0 +1,93 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use rust_ecom_service_core::auth::{
+    decode_verification_token, encode_verification_token, EmailVerificationClaims,
+    EMAIL_VERIFICATION_TOKEN_LIFETIME_HOURS,
+};
+use serde::Serialize;
+
+const SECRET: &[u8] = b"secret";
+
+#[derive(Serialize)]
+struct SessionClaims {
+    sub: i32,
+    email: String,
+    iat: usize,
+    exp: usize,
+}
+
+#[test]
+fn token_is_decoded_with_its_account_and_email() {
+    let token = encode_verification_token(SECRET, 7, "user@example.com", Utc::now());
+    let claims = decode_verification_token(SECRET, &token).unwrap();
+
+    assert_eq!(claims.sub, 7);
+    assert_eq!(claims.email, "user@example.com");
+}
+
+#[test]
+fn token_signed_with_another_secret_is_rejected() {
+    let token = encode_verification_token(b"other", 7, "user@example.com", Utc::now());
+
+    assert!(decode_verification_token(SECRET, &token).is_none());
+}
+
+#[test]
+fn expired_token_is_rejected() {
+    let issued = Utc::now() - Duration::hours(EMAIL_VERIFICATION_TOKEN_LIFETIME_HOURS + 1);
+    let token = encode_verification_token(SECRET, 7, "user@example.com", issued);
+
+    assert!(decode_verification_token(SECRET, &token).is_none());
+}
+
+#[test]
+fn token_for_another_audience_is_rejected() {
+    let now = Utc::now();
+    let claims = EmailVerificationClaims {
+        sub: 7,
+        aud: "password-reset".to_owned(),
+        email: "user@example.com".to_owned(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::hours(1)).timestamp() as usize,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(SECRET),
+    )
+    .unwrap();
+
+    assert!(decode_verification_token(SECRET, &token).is_none());
+}
+
+#[test]
+fn token_without_audience_is_rejected() {
+    let now = Utc::now();
+    let claims = SessionClaims {
+        sub: 7,
+        email: "user@example.com".to_owned(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::hours(1)).timestamp() as usize,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(SECRET),
+    )
+    .unwrap();
+
+    assert!(decode_verification_token(SECRET, &token).is_none());
+}
+
+#[test]
+fn tampered_token_is_rejected() {
+    let token = encode_verification_token(SECRET, 7, "user@example.com", Utc::now());
+    let mut parts: Vec<String> = token.split('.').map(str::to_owned).collect();
+    parts[1] = encode_verification_token(SECRET, 8, "user@example.com", Utc::now())
+        .split('.')
+        .nth(1)
+        .unwrap()
+        .to_owned();
+
+    assert!(decode_verification_token(SECRET, &parts.join(".")).is_none());
+}
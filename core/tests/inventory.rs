@@ -0,0 +1,64 @@
+use entity::location;
+use rust_ecom_service_core::inventory::{
+    is_back_in_stock, is_low_stock, total_available, LocationInventory,
+};
+
+fn location(id: i32) -> location::Model {
+    location::Model {
+        id,
+        label: format!("Location {}", id),
+        priority: id,
+    }
+}
+
+#[test]
+fn orders_hold_back_stock_on_hand() {
+    let inventory = vec![
+        LocationInventory::new(location(1), 10, 4),
+        LocationInventory::new(location(2), 3, 0),
+    ];
+
+    assert_eq!(inventory[0].available, 6);
+    assert_eq!(total_available(&inventory), 9);
+}
+
+#[test]
+fn product_sold_out_by_orders_is_back_in_stock_once_received() {
+    // Past receipts leave stock on hand, but every unit is promised to an order
+    let inventory = vec![LocationInventory::new(location(1), 5, 5)];
+    let available = total_available(&inventory);
+
+    assert_eq!(available, 0);
+    assert!(is_back_in_stock(available, 2));
+}
+
+#[test]
+fn receipts_covering_only_backorders_are_not_back_in_stock() {
+    let inventory = vec![LocationInventory::new(location(1), 5, 8)];
+    let available = total_available(&inventory);
+
+    assert!(!is_back_in_stock(available, 3));
+    assert!(is_back_in_stock(available, 4));
+}
+
+#[test]
+fn products_still_available_are_not_back_in_stock() {
+    assert!(!is_back_in_stock(1, 5));
+}
+
+#[test]
+fn order_pushes_availability_below_low_stock_threshold() {
+    let threshold = 5;
+    let before = vec![LocationInventory::new(location(1), 10, 0)];
+    // Nothing leaves the stock ledger until the order ships
+    let after = vec![LocationInventory::new(location(1), 10, 6)];
+
+    assert!(!is_low_stock(total_available(&before), threshold));
+    assert!(is_low_stock(total_available(&after), threshold));
+}
+
+#[test]
+fn availability_at_threshold_is_not_low() {
+    assert!(!is_low_stock(5, 5));
+    assert!(is_low_stock(4, 5));
+}
@@ -0,0 +1,107 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use entity::login_throttle;
+use rust_ecom_service_core::auth::{
+    login_failure_count, login_retry_date, ACCOUNT_LOGIN_POLICY, LOGIN_FAILURE_WINDOW_MINUTES,
+    LOGIN_MAX_BACKOFF_SECONDS, LOGIN_THROTTLE_ACCOUNT,
+};
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+fn throttle(failure_count: i32, last_failure_date: NaiveDateTime) -> login_throttle::Model {
+    login_throttle::Model {
+        id: 1,
+        kind: LOGIN_THROTTLE_ACCOUNT.to_owned(),
+        key: "user@example.com".to_owned(),
+        failure_count,
+        last_failure_date,
+        locked_until: None,
+    }
+}
+
+#[test]
+fn free_failures_need_no_wait() {
+    let throttle = throttle(ACCOUNT_LOGIN_POLICY.free_failures - 1, now());
+
+    assert_eq!(
+        login_retry_date(&throttle, &ACCOUNT_LOGIN_POLICY, now()),
+        None
+    );
+}
+
+#[test]
+fn wait_doubles_with_every_failure() {
+    for (extra_failures, seconds) in [(0, 1), (1, 2), (2, 4), (5, 32)] {
+        let throttle = throttle(ACCOUNT_LOGIN_POLICY.free_failures + extra_failures, now());
+
+        assert_eq!(
+            login_retry_date(&throttle, &ACCOUNT_LOGIN_POLICY, now()),
+            Some(now() + Duration::seconds(seconds))
+        );
+    }
+}
+
+#[test]
+fn wait_is_capped() {
+    for failure_count in [ACCOUNT_LOGIN_POLICY.free_failures + 9, i32::MAX] {
+        let throttle = throttle(failure_count, now());
+
+        assert_eq!(
+            login_retry_date(&throttle, &ACCOUNT_LOGIN_POLICY, now()),
+            Some(now() + Duration::seconds(LOGIN_MAX_BACKOFF_SECONDS))
+        );
+    }
+}
+
+#[test]
+fn no_wait_once_backoff_has_passed() {
+    let throttle = throttle(ACCOUNT_LOGIN_POLICY.free_failures + 2, now());
+
+    assert_eq!(
+        login_retry_date(
+            &throttle,
+            &ACCOUNT_LOGIN_POLICY,
+            now() + Duration::seconds(4)
+        ),
+        None
+    );
+}
+
+#[test]
+fn lockout_holds_until_it_ends() {
+    let locked_until = now() + Duration::minutes(10);
+    let throttle = login_throttle::Model {
+        locked_until: Some(locked_until),
+        ..throttle(
+            ACCOUNT_LOGIN_POLICY.lockout_failures,
+            now() - Duration::minutes(5),
+        )
+    };
+
+    assert_eq!(
+        login_retry_date(&throttle, &ACCOUNT_LOGIN_POLICY, now()),
+        Some(locked_until)
+    );
+    assert_eq!(
+        login_retry_date(&throttle, &ACCOUNT_LOGIN_POLICY, locked_until),
+        None
+    );
+}
+
+#[test]
+fn failures_are_forgotten_after_the_window() {
+    let last_failure_date = now() - Duration::minutes(LOGIN_FAILURE_WINDOW_MINUTES);
+    let recent = throttle(ACCOUNT_LOGIN_POLICY.lockout_failures - 1, now());
+    let stale = throttle(ACCOUNT_LOGIN_POLICY.lockout_failures - 1, last_failure_date);
+
+    assert_eq!(
+        login_failure_count(&recent, now()),
+        ACCOUNT_LOGIN_POLICY.lockout_failures - 1
+    );
+    assert_eq!(login_failure_count(&stale, now()), 0);
+    assert_eq!(login_retry_date(&stale, &ACCOUNT_LOGIN_POLICY, now()), None);
+}
@@ -0,0 +1,23 @@
+use rust_ecom_service_core::auth::{generate_token, hash_token};
+
+#[test]
+fn tokens_are_random() {
+    let token = generate_token();
+
+    assert_eq!(token.len(), 64);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(token, generate_token());
+}
+
+#[test]
+fn token_hashes_are_stable_and_hide_the_token() {
+    let token = generate_token();
+
+    assert_eq!(hash_token(&token), hash_token(&token));
+    assert_ne!(hash_token(&token), token);
+    assert_ne!(hash_token(&token), hash_token(&generate_token()));
+    assert_eq!(
+        hash_token("token"),
+        "3c469e9d6c5875d37a43f353d4f88e61fcf812c66eee3457465a40b0da4153e0"
+    );
+}
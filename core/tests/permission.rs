@@ -0,0 +1,58 @@
+use rust_ecom_service_core::permission::{AccountPermissions, Permission};
+use std::collections::HashSet;
+
+#[test]
+fn every_permission_parses_back_from_its_name() {
+    for permission in Permission::ALL {
+        assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+    }
+
+    let names: HashSet<_> = Permission::ALL.iter().map(|p| p.as_str()).collect();
+    assert_eq!(names.len(), Permission::ALL.len());
+}
+
+#[test]
+fn unknown_names_are_not_permissions() {
+    for name in [
+        "",
+        "admin",
+        "catalog",
+        "Catalog:Manage",
+        " catalog:manage",
+        "orders:*",
+    ] {
+        assert_eq!(Permission::parse(name), None);
+    }
+}
+
+#[test]
+fn accounts_only_have_granted_permissions() {
+    let none = AccountPermissions::default();
+    let permissions = AccountPermissions(HashSet::from([
+        Permission::RolesManage,
+        Permission::CatalogManage,
+    ]));
+
+    for permission in Permission::ALL {
+        assert!(!none.has(permission));
+    }
+    assert!(permissions.has(Permission::CatalogManage));
+    assert!(permissions.has(Permission::RolesManage));
+    assert!(!permissions.has(Permission::AccountsManage));
+    assert!(!permissions.has(Permission::OrdersView));
+}
+
+#[test]
+fn names_follow_the_order_of_all_permissions() {
+    let permissions = AccountPermissions(HashSet::from([
+        Permission::RolesManage,
+        Permission::OrdersView,
+        Permission::CatalogManage,
+    ]));
+
+    assert_eq!(
+        permissions.names(),
+        vec!["catalog:manage", "orders:view", "roles:manage"]
+    );
+    assert!(AccountPermissions::default().names().is_empty());
+}
@@ -0,0 +1,89 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use entity::{account_session, refresh_token};
+use rust_ecom_service_core::auth::{refresh_token_rejection, RefreshOutcome};
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+fn session() -> account_session::Model {
+    account_session::Model {
+        id: 1,
+        account_id: 1,
+        user_agent: None,
+        creation_date: now() - Duration::days(1),
+        last_used_date: now() - Duration::hours(1),
+        expiry_date: now() + Duration::days(1),
+        revoked_date: None,
+    }
+}
+
+fn token() -> refresh_token::Model {
+    refresh_token::Model {
+        id: 1,
+        session_id: 1,
+        token_hash: "hash".to_owned(),
+        creation_date: now() - Duration::hours(1),
+        expiry_date: now() + Duration::days(1),
+        used_date: None,
+    }
+}
+
+#[test]
+fn unused_token_on_active_session_is_accepted() {
+    assert!(refresh_token_rejection(&token(), &session(), now()).is_none());
+}
+
+#[test]
+fn used_token_is_reused() {
+    let token = refresh_token::Model {
+        used_date: Some(now() - Duration::minutes(5)),
+        ..token()
+    };
+
+    assert!(matches!(
+        refresh_token_rejection(&token, &session(), now()),
+        Some(RefreshOutcome::Reused)
+    ));
+}
+
+#[test]
+fn used_token_on_ended_session_is_invalid() {
+    let token = refresh_token::Model {
+        used_date: Some(now() - Duration::minutes(5)),
+        ..token()
+    };
+    let revoked = account_session::Model {
+        revoked_date: Some(now() - Duration::minutes(1)),
+        ..session()
+    };
+    let expired = account_session::Model {
+        expiry_date: now(),
+        ..session()
+    };
+
+    assert!(matches!(
+        refresh_token_rejection(&token, &revoked, now()),
+        Some(RefreshOutcome::Invalid)
+    ));
+    assert!(matches!(
+        refresh_token_rejection(&token, &expired, now()),
+        Some(RefreshOutcome::Invalid)
+    ));
+}
+
+#[test]
+fn expired_token_is_invalid() {
+    let token = refresh_token::Model {
+        expiry_date: now(),
+        ..token()
+    };
+
+    assert!(matches!(
+        refresh_token_rejection(&token, &session(), now()),
+        Some(RefreshOutcome::Invalid)
+    ));
+}
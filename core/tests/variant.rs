@@ -0,0 +1,29 @@
+use entity::attribute_option;
+use rust_ecom_service_core::catalog::is_option_selected;
+
+fn option(label: &str, content: &str) -> attribute_option::Model {
+    attribute_option::Model {
+        id: 1,
+        attribute_id: 1,
+        label: label.to_string(),
+        content: content.to_string(),
+        position: 0,
+    }
+}
+
+#[test]
+fn revision_value_selects_option_by_label_or_content() {
+    let red = option("Red", "#ff0000");
+
+    assert!(is_option_selected(&red, "Red"));
+    assert!(is_option_selected(&red, " red "));
+    assert!(is_option_selected(&red, "#FF0000"));
+}
+
+#[test]
+fn options_the_revision_did_not_pick_are_not_selected() {
+    let blue = option("Blue", "#0000ff");
+
+    assert!(!is_option_selected(&blue, "Red"));
+    assert!(!is_option_selected(&blue, ""));
+}
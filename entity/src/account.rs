@@ -12,12 +12,40 @@ pub struct Model {
     pub email: String,
     pub password: String,
     pub role: String,
+    pub email_verified: bool,
+    pub verification_email_date: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::account_role::Entity")]
+    AccountRole,
+    #[sea_orm(has_many = "super::account_session::Entity")]
+    AccountSession,
+    #[sea_orm(has_many = "super::media::Entity")]
+    Media,
     #[sea_orm(has_many = "super::order::Entity")]
     Order,
+    #[sea_orm(has_many = "super::password_reset_token::Entity")]
+    PasswordResetToken,
+}
+
+impl Related<super::account_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccountRole.def()
+    }
+}
+
+impl Related<super::account_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccountSession.def()
+    }
+}
+
+impl Related<super::media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Media.def()
+    }
 }
 
 impl Related<super::order::Entity> for Entity {
@@ -26,4 +54,10 @@ impl Related<super::order::Entity> for Entity {
     }
 }
 
+impl Related<super::password_reset_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordResetToken.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
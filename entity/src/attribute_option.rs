@@ -11,6 +11,7 @@ pub struct Model {
     pub attribute_id: i32,
     pub label: String,
     pub content: String,
+    pub position: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
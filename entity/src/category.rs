@@ -10,6 +10,7 @@ pub struct Model {
     pub id: i32,
     pub label: String,
     pub parent_id: Option<i32>,
+    pub position: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +25,8 @@ pub enum Relation {
     SelfRef,
     #[sea_orm(has_many = "super::product::Entity")]
     Product,
+    #[sea_orm(has_many = "super::product_category::Entity")]
+    ProductCategory,
 }
 
 impl Related<super::product::Entity> for Entity {
@@ -32,4 +35,10 @@ impl Related<super::product::Entity> for Entity {
     }
 }
 
+impl Related<super::product_category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductCategory.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -3,18 +3,35 @@
 pub mod prelude;
 
 pub mod account;
+pub mod account_role;
+pub mod account_session;
 pub mod address;
 pub mod attribute;
 pub mod attribute_option;
 pub mod category;
+pub mod image_variant;
+pub mod location;
+pub mod login_event;
+pub mod login_throttle;
+pub mod media;
 pub mod order;
 pub mod order_attribute;
 pub mod order_item;
 pub mod order_item_attribute;
 pub mod order_note;
+pub mod password_reset_token;
 pub mod product;
 pub mod product_attribute;
+pub mod product_category;
 pub mod product_detail;
+pub mod product_event;
 pub mod product_image;
+pub mod product_variant;
+pub mod product_variant_image;
+pub mod product_variant_option;
+pub mod refresh_token;
+pub mod restock_subscription;
+pub mod role;
+pub mod role_permission;
 pub mod static_page;
 pub mod stock;
@@ -12,10 +12,20 @@ pub struct Model {
     pub product_id: i32,
     pub price: Decimal,
     pub qty: i32,
+    pub location_id: Option<i32>,
+    pub variant_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::location::Entity",
+        from = "Column::LocationId",
+        to = "super::location::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Location,
     #[sea_orm(
         belongs_to = "super::order::Entity",
         from = "Column::OrderId",
@@ -34,6 +44,20 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     ProductDetail,
+    #[sea_orm(
+        belongs_to = "super::product_variant::Entity",
+        from = "Column::VariantId",
+        to = "super::product_variant::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    ProductVariant,
+}
+
+impl Related<super::location::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Location.def()
+    }
 }
 
 impl Related<super::order::Entity> for Entity {
@@ -54,4 +78,10 @@ impl Related<super::product_detail::Entity> for Entity {
     }
 }
 
+impl Related<super::product_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductVariant.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,18 +1,35 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.4
 
 pub use super::account::Entity as Account;
+pub use super::account_role::Entity as AccountRole;
+pub use super::account_session::Entity as AccountSession;
 pub use super::address::Entity as Address;
 pub use super::attribute::Entity as Attribute;
 pub use super::attribute_option::Entity as AttributeOption;
 pub use super::category::Entity as Category;
+pub use super::image_variant::Entity as ImageVariant;
+pub use super::location::Entity as Location;
+pub use super::login_event::Entity as LoginEvent;
+pub use super::login_throttle::Entity as LoginThrottle;
+pub use super::media::Entity as Media;
 pub use super::order::Entity as Order;
 pub use super::order_attribute::Entity as OrderAttribute;
 pub use super::order_item::Entity as OrderItem;
 pub use super::order_item_attribute::Entity as OrderItemAttribute;
 pub use super::order_note::Entity as OrderNote;
+pub use super::password_reset_token::Entity as PasswordResetToken;
 pub use super::product::Entity as Product;
 pub use super::product_attribute::Entity as ProductAttribute;
+pub use super::product_category::Entity as ProductCategory;
 pub use super::product_detail::Entity as ProductDetail;
+pub use super::product_event::Entity as ProductEvent;
 pub use super::product_image::Entity as ProductImage;
+pub use super::product_variant::Entity as ProductVariant;
+pub use super::product_variant_image::Entity as ProductVariantImage;
+pub use super::product_variant_option::Entity as ProductVariantOption;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::restock_subscription::Entity as RestockSubscription;
+pub use super::role::Entity as Role;
+pub use super::role_permission::Entity as RolePermission;
 pub use super::static_page::Entity as StaticPage;
 pub use super::stock::Entity as Stock;
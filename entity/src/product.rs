@@ -13,6 +13,11 @@ pub struct Model {
     pub revision_id: i32,
     pub allow_back_order: bool,
     pub allow_restock_notifications: bool,
+    pub low_stock_threshold: Option<i32>,
+    pub low_stock_alert_date: Option<DateTime>,
+    pub status: String,
+    pub publish_date: Option<DateTime>,
+    pub unpublish_date: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -33,6 +38,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     ProductDetail,
+    #[sea_orm(has_many = "super::product_category::Entity")]
+    ProductCategory,
+    #[sea_orm(has_many = "super::product_event::Entity")]
+    ProductEvent,
+    #[sea_orm(has_many = "super::product_variant::Entity")]
+    ProductVariant,
+    #[sea_orm(has_many = "super::restock_subscription::Entity")]
+    RestockSubscription,
     #[sea_orm(has_many = "super::stock::Entity")]
     Stock,
 }
@@ -49,6 +62,30 @@ impl Related<super::product_detail::Entity> for Entity {
     }
 }
 
+impl Related<super::product_category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductCategory.def()
+    }
+}
+
+impl Related<super::product_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductEvent.def()
+    }
+}
+
+impl Related<super::product_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductVariant.def()
+    }
+}
+
+impl Related<super::restock_subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RestockSubscription.def()
+    }
+}
+
 impl Related<super::stock::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Stock.def()
@@ -15,6 +15,10 @@ pub struct Model {
     pub real_weight: Option<i32>,
     pub ship_weight: Option<i32>,
     pub parent_id: Option<i32>,
+    pub product_id: Option<i32>,
+    pub status: String,
+    pub creation_date: DateTime,
+    pub scheduled_date: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -23,8 +27,18 @@ pub enum Relation {
     OrderItem,
     #[sea_orm(has_many = "super::product::Entity")]
     Product,
+    #[sea_orm(
+        belongs_to = "super::product::Entity",
+        from = "Column::ProductId",
+        to = "super::product::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    RevisionOf,
     #[sea_orm(has_many = "super::product_attribute::Entity")]
     ProductAttribute,
+    #[sea_orm(has_many = "super::product_event::Entity")]
+    ProductEvent,
     #[sea_orm(
         belongs_to = "Entity",
         from = "Column::ParentId",
@@ -55,6 +69,12 @@ impl Related<super::product_attribute::Entity> for Entity {
     }
 }
 
+impl Related<super::product_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductEvent.def()
+    }
+}
+
 impl Related<super::product_image::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ProductImage.def()
@@ -11,6 +11,7 @@ pub struct Model {
     pub hash_id: Uuid,
     pub product_id: i32,
     pub position: i32,
+    pub alt_text: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -11,10 +11,22 @@ pub struct Model {
     pub product_id: i32,
     pub amount: i32,
     pub addition_date: DateTime,
+    pub location_id: i32,
+    pub kind: String,
+    pub transfer_id: Option<Uuid>,
+    pub variant_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::location::Entity",
+        from = "Column::LocationId",
+        to = "super::location::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Location,
     #[sea_orm(
         belongs_to = "super::product::Entity",
         from = "Column::ProductId",
@@ -23,6 +35,20 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Product,
+    #[sea_orm(
+        belongs_to = "super::product_variant::Entity",
+        from = "Column::VariantId",
+        to = "super::product_variant::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    ProductVariant,
+}
+
+impl Related<super::location::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Location.def()
+    }
 }
 
 impl Related<super::product::Entity> for Entity {
@@ -31,4 +57,10 @@ impl Related<super::product::Entity> for Entity {
     }
 }
 
+impl Related<super::product_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductVariant.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,14 +1,52 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220120_000001_create_preliminary_tables;
+mod m20231101_000001_create_restock_subscription_table;
+mod m20231101_000002_add_product_low_stock_threshold;
+mod m20231101_000003_create_location_table;
+mod m20231101_000004_make_product_short_url_unique;
+mod m20231101_000005_add_product_detail_revision_fields;
+mod m20231101_000006_add_product_visibility;
+mod m20231101_000007_create_product_variant_tables;
+mod m20231101_000008_add_product_detail_search_vector;
+mod m20231101_000009_add_category_position;
+mod m20231101_000010_create_product_category_table;
+mod m20231101_000011_add_attribute_option_position;
+mod m20231101_000012_add_product_image_alt_text;
+mod m20231101_000013_create_image_variant_table;
+mod m20231101_000014_create_media_table;
+mod m20231101_000015_create_password_reset_token_table;
+mod m20231101_000016_create_account_session_tables;
+mod m20231101_000017_add_account_email_verification;
+mod m20231101_000018_create_role_tables;
+mod m20231101_000019_create_login_throttle_tables;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(
-            m20220120_000001_create_preliminary_tables::Migration,
-        )]
+        vec![
+            Box::new(m20220120_000001_create_preliminary_tables::Migration),
+            Box::new(m20231101_000001_create_restock_subscription_table::Migration),
+            Box::new(m20231101_000002_add_product_low_stock_threshold::Migration),
+            Box::new(m20231101_000003_create_location_table::Migration),
+            Box::new(m20231101_000004_make_product_short_url_unique::Migration),
+            Box::new(m20231101_000005_add_product_detail_revision_fields::Migration),
+            Box::new(m20231101_000006_add_product_visibility::Migration),
+            Box::new(m20231101_000007_create_product_variant_tables::Migration),
+            Box::new(m20231101_000008_add_product_detail_search_vector::Migration),
+            Box::new(m20231101_000009_add_category_position::Migration),
+            Box::new(m20231101_000010_create_product_category_table::Migration),
+            Box::new(m20231101_000011_add_attribute_option_position::Migration),
+            Box::new(m20231101_000012_add_product_image_alt_text::Migration),
+            Box::new(m20231101_000013_create_image_variant_table::Migration),
+            Box::new(m20231101_000014_create_media_table::Migration),
+            Box::new(m20231101_000015_create_password_reset_token_table::Migration),
+            Box::new(m20231101_000016_create_account_session_tables::Migration),
+            Box::new(m20231101_000017_add_account_email_verification::Migration),
+            Box::new(m20231101_000018_create_role_tables::Migration),
+            Box::new(m20231101_000019_create_login_throttle_tables::Migration),
+        ]
     }
 }
@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+const RESTOCK_SUBSCRIPTION_PRODUCT_ID_INDEX_NAME: &str = "idx_restock-subscription_product-id";
+
+const RESTOCK_SUBSCRIPTION_PRODUCT_ID_FK_NAME: &str = "fk_restock-subscription_product-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RestockSubscription::Table)
+                    .col(
+                        ColumnDef::new(RestockSubscription::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(RestockSubscription::ProductId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RestockSubscription::Email)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RestockSubscription::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RestockSubscription::FulfilledDate).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(RESTOCK_SUBSCRIPTION_PRODUCT_ID_FK_NAME)
+                            .from(RestockSubscription::Table, RestockSubscription::ProductId)
+                            .to(Product::Table, Product::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(RESTOCK_SUBSCRIPTION_PRODUCT_ID_INDEX_NAME)
+                    .table(RestockSubscription::Table)
+                    .col(RestockSubscription::ProductId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(RESTOCK_SUBSCRIPTION_PRODUCT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RestockSubscription::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum RestockSubscription {
+    Table,
+    Id,
+    ProductId,
+    Email,
+    CreationDate,
+    FulfilledDate,
+}
@@ -0,0 +1,189 @@
+use sea_orm_migration::prelude::*;
+
+const DEFAULT_LOCATION_LABEL: &str = "Main Warehouse";
+const DEFAULT_STOCK_KIND: &str = "receipt";
+
+const STOCK_PRODUCT_ID_LOCATION_ID_INDEX_NAME: &str = "idx_stock_product-id_location-id";
+
+const STOCK_LOCATION_ID_FK_NAME: &str = "fk_stock_location-id";
+const ORDER_ITEM_LOCATION_ID_FK_NAME: &str = "fk_order-item_location-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Location::Table)
+                    .col(
+                        ColumnDef::new(Location::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Location::Label).string().not_null())
+                    .col(
+                        ColumnDef::new(Location::Priority)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Location::Table)
+                    .columns([Location::Label, Location::Priority])
+                    .values_panic([DEFAULT_LOCATION_LABEL.into(), 0.into()])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Stock::Table)
+                    .add_column(ColumnDef::new(Stock::LocationId).integer())
+                    .add_column(
+                        ColumnDef::new(Stock::Kind)
+                            .string()
+                            .not_null()
+                            .default(DEFAULT_STOCK_KIND),
+                    )
+                    .add_column(ColumnDef::new(Stock::TransferId).uuid())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing stock was all received at the one location we had before
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Stock::Table)
+                    .value(
+                        Stock::LocationId,
+                        Expr::cust(r#"(SELECT MIN("id") FROM "location")"#),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Stock::Table)
+                    .modify_column(ColumnDef::new(Stock::LocationId).integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(STOCK_LOCATION_ID_FK_NAME)
+                    .from(Stock::Table, Stock::LocationId)
+                    .to(Location::Table, Location::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(STOCK_PRODUCT_ID_LOCATION_ID_INDEX_NAME)
+                    .table(Stock::Table)
+                    .col(Stock::ProductId)
+                    .col(Stock::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderItem::Table)
+                    .add_column(ColumnDef::new(OrderItem::LocationId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(ORDER_ITEM_LOCATION_ID_FK_NAME)
+                    .from(OrderItem::Table, OrderItem::LocationId)
+                    .to(Location::Table, Location::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderItem::Table)
+                    .drop_foreign_key(Alias::new(ORDER_ITEM_LOCATION_ID_FK_NAME))
+                    .drop_column(OrderItem::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(STOCK_PRODUCT_ID_LOCATION_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Stock::Table)
+                    .drop_foreign_key(Alias::new(STOCK_LOCATION_ID_FK_NAME))
+                    .drop_column(Stock::TransferId)
+                    .drop_column(Stock::Kind)
+                    .drop_column(Stock::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Location::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Location {
+    Table,
+    Id,
+    Label,
+    Priority,
+}
+
+#[derive(Iden)]
+enum Stock {
+    Table,
+    ProductId,
+    LocationId,
+    Kind,
+    TransferId,
+}
+
+#[derive(Iden)]
+enum OrderItem {
+    Table,
+    LocationId,
+}
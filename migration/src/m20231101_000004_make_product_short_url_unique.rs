@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+const PRODUCT_SHORT_URL_INDEX_NAME: &str = "idx_product_short-url";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(PRODUCT_SHORT_URL_INDEX_NAME).to_owned())
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_SHORT_URL_INDEX_NAME)
+                    .table(Product::Table)
+                    .col(Product::ShortUrl)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(PRODUCT_SHORT_URL_INDEX_NAME).to_owned())
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_SHORT_URL_INDEX_NAME)
+                    .table(Product::Table)
+                    .col(Product::ShortUrl)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    ShortUrl,
+}
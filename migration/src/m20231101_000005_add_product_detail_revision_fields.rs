@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+
+const DEFAULT_REVISION_STATUS: &str = "published";
+
+const PRODUCT_DETAIL_PRODUCT_ID_INDEX_NAME: &str = "idx_product-detail_product-id";
+
+const PRODUCT_DETAIL_PRODUCT_ID_FK_NAME: &str = "fk_product-detail_product-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .add_column(ColumnDef::new(ProductDetail::ProductId).integer())
+                    .add_column(
+                        ColumnDef::new(ProductDetail::Status)
+                            .string()
+                            .not_null()
+                            .default(DEFAULT_REVISION_STATUS),
+                    )
+                    .add_column(
+                        ColumnDef::new(ProductDetail::CreationDate)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing revisions belong to whichever product's live revision they lead up to
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE "product_detail" SET "product_id" = "chain"."product_id"
+                FROM (
+                    WITH RECURSIVE "chain" AS (
+                        SELECT "product"."id" AS "product_id", "product_detail"."id" AS "revision_id", "product_detail"."parent_id"
+                        FROM "product"
+                        INNER JOIN "product_detail" ON "product_detail"."id" = "product"."revision_id"
+                        UNION
+                        SELECT "chain"."product_id", "product_detail"."id", "product_detail"."parent_id"
+                        FROM "chain"
+                        INNER JOIN "product_detail" ON "product_detail"."id" = "chain"."parent_id"
+                    )
+                    SELECT "product_id", "revision_id" FROM "chain"
+                ) AS "chain"
+                WHERE "product_detail"."id" = "chain"."revision_id""#,
+            )
+            .await?;
+
+        // Revisions are removed after their product, so the link is cleared rather than blocking it
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(PRODUCT_DETAIL_PRODUCT_ID_FK_NAME)
+                    .from(ProductDetail::Table, ProductDetail::ProductId)
+                    .to(Product::Table, Product::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_DETAIL_PRODUCT_ID_INDEX_NAME)
+                    .table(ProductDetail::Table)
+                    .col(ProductDetail::ProductId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_DETAIL_PRODUCT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .drop_foreign_key(Alias::new(PRODUCT_DETAIL_PRODUCT_ID_FK_NAME))
+                    .drop_column(ProductDetail::CreationDate)
+                    .drop_column(ProductDetail::Status)
+                    .drop_column(ProductDetail::ProductId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum ProductDetail {
+    Table,
+    ProductId,
+    Status,
+    CreationDate,
+}
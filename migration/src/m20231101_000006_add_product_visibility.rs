@@ -0,0 +1,149 @@
+use sea_orm_migration::prelude::*;
+
+const DEFAULT_PRODUCT_STATUS: &str = "active";
+
+const PRODUCT_EVENT_PRODUCT_ID_INDEX_NAME: &str = "idx_product-event_product-id";
+
+const PRODUCT_EVENT_PRODUCT_ID_FK_NAME: &str = "fk_product-event_product-id";
+const PRODUCT_EVENT_REVISION_ID_FK_NAME: &str = "fk_product-event_revision-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .add_column(
+                        ColumnDef::new(Product::Status)
+                            .string()
+                            .not_null()
+                            .default(DEFAULT_PRODUCT_STATUS),
+                    )
+                    .add_column(ColumnDef::new(Product::PublishDate).date_time())
+                    .add_column(ColumnDef::new(Product::UnpublishDate).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .add_column(ColumnDef::new(ProductDetail::ScheduledDate).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductEvent::Table)
+                    .col(
+                        ColumnDef::new(ProductEvent::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(ProductEvent::ProductId).integer().not_null())
+                    .col(ColumnDef::new(ProductEvent::RevisionId).integer())
+                    .col(ColumnDef::new(ProductEvent::Kind).string().not_null())
+                    .col(
+                        ColumnDef::new(ProductEvent::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_EVENT_PRODUCT_ID_FK_NAME)
+                            .from(ProductEvent::Table, ProductEvent::ProductId)
+                            .to(Product::Table, Product::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_EVENT_REVISION_ID_FK_NAME)
+                            .from(ProductEvent::Table, ProductEvent::RevisionId)
+                            .to(ProductDetail::Table, ProductDetail::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_EVENT_PRODUCT_ID_INDEX_NAME)
+                    .table(ProductEvent::Table)
+                    .col(ProductEvent::ProductId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_EVENT_PRODUCT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProductEvent::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .drop_column(ProductDetail::ScheduledDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .drop_column(Product::UnpublishDate)
+                    .drop_column(Product::PublishDate)
+                    .drop_column(Product::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    Id,
+    Status,
+    PublishDate,
+    UnpublishDate,
+}
+
+#[derive(Iden)]
+enum ProductDetail {
+    Table,
+    Id,
+    ScheduledDate,
+}
+
+#[derive(Iden)]
+enum ProductEvent {
+    Table,
+    Id,
+    ProductId,
+    RevisionId,
+    Kind,
+    CreationDate,
+}
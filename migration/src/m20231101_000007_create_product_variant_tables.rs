@@ -0,0 +1,352 @@
+use sea_orm_migration::prelude::*;
+
+const PRODUCT_VARIANT_PRODUCT_ID_INDEX_NAME: &str = "idx_product-variant_product-id";
+const PRODUCT_VARIANT_SKU_INDEX_NAME: &str = "idx_product-variant_sku";
+const PRODUCT_VARIANT_OPTION_VARIANT_ID_INDEX_NAME: &str = "idx_product-variant-option_variant-id";
+const PRODUCT_VARIANT_IMAGE_VARIANT_ID_INDEX_NAME: &str = "idx_product-variant-image_variant-id";
+
+const PRODUCT_VARIANT_PRODUCT_ID_FK_NAME: &str = "fk_product-variant_product-id";
+const PRODUCT_VARIANT_OPTION_VARIANT_ID_FK_NAME: &str = "fk_product-variant-option_variant-id";
+const PRODUCT_VARIANT_OPTION_ATTRIBUTE_ID_FK_NAME: &str = "fk_product-variant-option_attribute-id";
+const PRODUCT_VARIANT_OPTION_ATTRIBUTE_OPTION_ID_FK_NAME: &str =
+    "fk_product-variant-option_attribute-option-id";
+const PRODUCT_VARIANT_IMAGE_VARIANT_ID_FK_NAME: &str = "fk_product-variant-image_variant-id";
+const STOCK_VARIANT_ID_FK_NAME: &str = "fk_stock_variant-id";
+const ORDER_ITEM_VARIANT_ID_FK_NAME: &str = "fk_order-item_variant-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductVariant::Table)
+                    .col(
+                        ColumnDef::new(ProductVariant::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariant::ProductId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ProductVariant::Sku).string().not_null())
+                    .col(ColumnDef::new(ProductVariant::Price).decimal())
+                    .col(
+                        ColumnDef::new(ProductVariant::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_VARIANT_PRODUCT_ID_FK_NAME)
+                            .from(ProductVariant::Table, ProductVariant::ProductId)
+                            .to(Product::Table, Product::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_VARIANT_PRODUCT_ID_INDEX_NAME)
+                    .table(ProductVariant::Table)
+                    .col(ProductVariant::ProductId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_VARIANT_SKU_INDEX_NAME)
+                    .table(ProductVariant::Table)
+                    .col(ProductVariant::Sku)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductVariantOption::Table)
+                    .col(
+                        ColumnDef::new(ProductVariantOption::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantOption::VariantId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantOption::AttributeId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantOption::AttributeOptionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_VARIANT_OPTION_VARIANT_ID_FK_NAME)
+                            .from(ProductVariantOption::Table, ProductVariantOption::VariantId)
+                            .to(ProductVariant::Table, ProductVariant::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_VARIANT_OPTION_ATTRIBUTE_ID_FK_NAME)
+                            .from(
+                                ProductVariantOption::Table,
+                                ProductVariantOption::AttributeId,
+                            )
+                            .to(Attribute::Table, Attribute::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_VARIANT_OPTION_ATTRIBUTE_OPTION_ID_FK_NAME)
+                            .from(
+                                ProductVariantOption::Table,
+                                ProductVariantOption::AttributeOptionId,
+                            )
+                            .to(AttributeOption::Table, AttributeOption::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_VARIANT_OPTION_VARIANT_ID_INDEX_NAME)
+                    .table(ProductVariantOption::Table)
+                    .col(ProductVariantOption::VariantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductVariantImage::Table)
+                    .col(
+                        ColumnDef::new(ProductVariantImage::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantImage::VariantId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantImage::HashId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductVariantImage::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_VARIANT_IMAGE_VARIANT_ID_FK_NAME)
+                            .from(ProductVariantImage::Table, ProductVariantImage::VariantId)
+                            .to(ProductVariant::Table, ProductVariant::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_VARIANT_IMAGE_VARIANT_ID_INDEX_NAME)
+                    .table(ProductVariantImage::Table)
+                    .col(ProductVariantImage::VariantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Stock::Table)
+                    .add_column(ColumnDef::new(Stock::VariantId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(STOCK_VARIANT_ID_FK_NAME)
+                    .from(Stock::Table, Stock::VariantId)
+                    .to(ProductVariant::Table, ProductVariant::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderItem::Table)
+                    .add_column(ColumnDef::new(OrderItem::VariantId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(ORDER_ITEM_VARIANT_ID_FK_NAME)
+                    .from(OrderItem::Table, OrderItem::VariantId)
+                    .to(ProductVariant::Table, ProductVariant::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderItem::Table)
+                    .drop_foreign_key(Alias::new(ORDER_ITEM_VARIANT_ID_FK_NAME))
+                    .drop_column(OrderItem::VariantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Stock::Table)
+                    .drop_foreign_key(Alias::new(STOCK_VARIANT_ID_FK_NAME))
+                    .drop_column(Stock::VariantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_VARIANT_IMAGE_VARIANT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProductVariantImage::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_VARIANT_OPTION_VARIANT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProductVariantOption::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_VARIANT_SKU_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_VARIANT_PRODUCT_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProductVariant::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Attribute {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum AttributeOption {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum ProductVariant {
+    Table,
+    Id,
+    ProductId,
+    Sku,
+    Price,
+    Active,
+}
+
+#[derive(Iden)]
+enum ProductVariantOption {
+    Table,
+    Id,
+    VariantId,
+    AttributeId,
+    AttributeOptionId,
+}
+
+#[derive(Iden)]
+enum ProductVariantImage {
+    Table,
+    Id,
+    VariantId,
+    HashId,
+    Position,
+}
+
+#[derive(Iden)]
+enum Stock {
+    Table,
+    VariantId,
+}
+
+#[derive(Iden)]
+enum OrderItem {
+    Table,
+    VariantId,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+const PRODUCT_DETAIL_SEARCH_VECTOR_INDEX_NAME: &str = "idx_product-detail_search-vector";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .add_column(
+                        ColumnDef::new(ProductDetail::SearchVector).custom(Alias::new("tsvector")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Kept in step with the refresh run whenever a revision is written
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE "product_detail" SET "search_vector" =
+                    setweight(to_tsvector('english', coalesce("product_detail"."name", '')), 'A') ||
+                    setweight(to_tsvector('simple', coalesce("product_detail"."upc", '')), 'A') ||
+                    setweight(to_tsvector('english', coalesce("product_detail"."description", '')), 'B') ||
+                    setweight(to_tsvector('english', coalesce((
+                        SELECT string_agg("product_attribute"."content", ' ')
+                        FROM "product_attribute"
+                        WHERE "product_attribute"."product_id" = "product_detail"."id"
+                    ), '')), 'C')"#,
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"CREATE INDEX "{}" ON "product_detail" USING GIN ("search_vector")"#,
+                PRODUCT_DETAIL_SEARCH_VECTOR_INDEX_NAME
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(PRODUCT_DETAIL_SEARCH_VECTOR_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductDetail::Table)
+                    .drop_column(ProductDetail::SearchVector)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ProductDetail {
+    Table,
+    SearchVector,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+const CATEGORY_PARENT_ID_INDEX_NAME: &str = "idx_category_parent-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Category::Table)
+                    .add_column(
+                        ColumnDef::new(Category::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing siblings keep the alphabetical order they were listed in
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE "category" SET "position" = "ordered"."position"
+                FROM (
+                    SELECT "id", ROW_NUMBER() OVER (PARTITION BY "parent_id" ORDER BY "label", "id") - 1 AS "position"
+                    FROM "category"
+                ) AS "ordered"
+                WHERE "category"."id" = "ordered"."id""#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(CATEGORY_PARENT_ID_INDEX_NAME)
+                    .table(Category::Table)
+                    .col(Category::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(CATEGORY_PARENT_ID_INDEX_NAME).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Category::Table)
+                    .drop_column(Category::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Category {
+    Table,
+    ParentId,
+    Position,
+}
@@ -0,0 +1,112 @@
+use sea_orm_migration::prelude::*;
+
+const PRODUCT_CATEGORY_PRODUCT_ID_INDEX_NAME: &str = "idx_product-category_product-id";
+const PRODUCT_CATEGORY_CATEGORY_ID_INDEX_NAME: &str = "idx_product-category_category-id";
+
+const PRODUCT_CATEGORY_PRODUCT_ID_FK_NAME: &str = "fk_product-category_product-id";
+const PRODUCT_CATEGORY_CATEGORY_ID_FK_NAME: &str = "fk_product-category_category-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductCategory::Table)
+                    .col(
+                        ColumnDef::new(ProductCategory::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductCategory::ProductId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductCategory::CategoryId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_CATEGORY_PRODUCT_ID_FK_NAME)
+                            .from(ProductCategory::Table, ProductCategory::ProductId)
+                            .to(Product::Table, Product::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PRODUCT_CATEGORY_CATEGORY_ID_FK_NAME)
+                            .from(ProductCategory::Table, ProductCategory::CategoryId)
+                            .to(Category::Table, Category::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_CATEGORY_PRODUCT_ID_INDEX_NAME)
+                    .table(ProductCategory::Table)
+                    .col(ProductCategory::ProductId)
+                    .col(ProductCategory::CategoryId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PRODUCT_CATEGORY_CATEGORY_ID_INDEX_NAME)
+                    .table(ProductCategory::Table)
+                    .col(ProductCategory::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The single category each product had so far becomes its primary membership
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"INSERT INTO "product_category" ("product_id", "category_id")
+                SELECT "id", "category_id" FROM "product" WHERE "category_id" IS NOT NULL"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProductCategory::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Category {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum ProductCategory {
+    Table,
+    Id,
+    ProductId,
+    CategoryId,
+}
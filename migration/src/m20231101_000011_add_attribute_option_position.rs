@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+const ATTRIBUTE_OPTION_ATTRIBUTE_ID_INDEX_NAME: &str = "idx_attribute-option_attribute-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AttributeOption::Table)
+                    .add_column(
+                        ColumnDef::new(AttributeOption::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Options keep the order they were created in
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE "attribute_option" SET "position" = "ordered"."position"
+                FROM (
+                    SELECT "id", ROW_NUMBER() OVER (PARTITION BY "attribute_id" ORDER BY "id") - 1 AS "position"
+                    FROM "attribute_option"
+                ) AS "ordered"
+                WHERE "attribute_option"."id" = "ordered"."id""#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ATTRIBUTE_OPTION_ATTRIBUTE_ID_INDEX_NAME)
+                    .table(AttributeOption::Table)
+                    .col(AttributeOption::AttributeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(ATTRIBUTE_OPTION_ATTRIBUTE_ID_INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AttributeOption::Table)
+                    .drop_column(AttributeOption::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AttributeOption {
+    Table,
+    AttributeId,
+    Position,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+const IMAGE_VARIANT_HASH_ID_INDEX_NAME: &str = "idx_image-variant_hash-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImageVariant::Table)
+                    .col(
+                        ColumnDef::new(ImageVariant::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(ImageVariant::HashId).uuid().not_null())
+                    .col(ColumnDef::new(ImageVariant::Name).string().not_null())
+                    .col(ColumnDef::new(ImageVariant::Key).string().not_null())
+                    .col(ColumnDef::new(ImageVariant::Width).integer().not_null())
+                    .col(ColumnDef::new(ImageVariant::Height).integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(IMAGE_VARIANT_HASH_ID_INDEX_NAME)
+                    .table(ImageVariant::Table)
+                    .col(ImageVariant::HashId)
+                    .col(ImageVariant::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImageVariant::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ImageVariant {
+    Table,
+    Id,
+    HashId,
+    Name,
+    Key,
+    Width,
+    Height,
+}
@@ -0,0 +1,115 @@
+use sea_orm_migration::prelude::*;
+
+const MEDIA_HASH_ID_INDEX_NAME: &str = "idx_media_hash-id";
+const MEDIA_CREATION_DATE_INDEX_NAME: &str = "idx_media_creation-date";
+
+const MEDIA_ACCOUNT_ID_FK_NAME: &str = "fk_media_account-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Media::HashId).uuid().not_null())
+                    .col(ColumnDef::new(Media::AccountId).integer())
+                    .col(ColumnDef::new(Media::FileName).string().not_null())
+                    .col(ColumnDef::new(Media::ContentType).string().not_null())
+                    .col(ColumnDef::new(Media::Width).integer().not_null())
+                    .col(ColumnDef::new(Media::Height).integer().not_null())
+                    .col(ColumnDef::new(Media::Size).big_integer().not_null())
+                    .col(ColumnDef::new(Media::CreationDate).date_time().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(MEDIA_ACCOUNT_ID_FK_NAME)
+                            .from(Media::Table, Media::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(MEDIA_HASH_ID_INDEX_NAME)
+                    .table(Media::Table)
+                    .col(Media::HashId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(MEDIA_CREATION_DATE_INDEX_NAME)
+                    .table(Media::Table)
+                    .col(Media::CreationDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Uploads made before the library existed get an entry sized after their largest
+        // stored copy, originals are always stored as WebP
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"INSERT INTO "media" ("hash_id", "file_name", "content_type", "width", "height", "size", "creation_date")
+                SELECT "upload"."hash_id", "upload"."hash_id"::text || '.webp', 'image/webp',
+                    coalesce(max("image_variant"."width"), 0), coalesce(max("image_variant"."height"), 0), 0,
+                    now() AT TIME ZONE 'UTC'
+                FROM (
+                    SELECT "hash_id" FROM "product_image"
+                    UNION
+                    SELECT "hash_id" FROM "product_variant_image"
+                    UNION
+                    SELECT "hash_id" FROM "image_variant"
+                ) AS "upload"
+                LEFT JOIN "image_variant" ON "image_variant"."hash_id" = "upload"."hash_id"
+                GROUP BY "upload"."hash_id""#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Media {
+    Table,
+    Id,
+    HashId,
+    AccountId,
+    FileName,
+    ContentType,
+    Width,
+    Height,
+    Size,
+    CreationDate,
+}
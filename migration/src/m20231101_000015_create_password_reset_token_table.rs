@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+const PASSWORD_RESET_TOKEN_TOKEN_HASH_INDEX_NAME: &str = "idx_password-reset-token_token-hash";
+const PASSWORD_RESET_TOKEN_ACCOUNT_ID_INDEX_NAME: &str = "idx_password-reset-token_account-id";
+
+const PASSWORD_RESET_TOKEN_ACCOUNT_ID_FK_NAME: &str = "fk_password-reset-token_account-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordResetToken::Table)
+                    .col(
+                        ColumnDef::new(PasswordResetToken::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::AccountId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::TokenHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::ExpiryDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PasswordResetToken::UsedDate).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(PASSWORD_RESET_TOKEN_ACCOUNT_ID_FK_NAME)
+                            .from(PasswordResetToken::Table, PasswordResetToken::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PASSWORD_RESET_TOKEN_TOKEN_HASH_INDEX_NAME)
+                    .table(PasswordResetToken::Table)
+                    .col(PasswordResetToken::TokenHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(PASSWORD_RESET_TOKEN_ACCOUNT_ID_INDEX_NAME)
+                    .table(PasswordResetToken::Table)
+                    .col(PasswordResetToken::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PasswordResetToken::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PasswordResetToken {
+    Table,
+    Id,
+    AccountId,
+    TokenHash,
+    CreationDate,
+    ExpiryDate,
+    UsedDate,
+}
@@ -0,0 +1,167 @@
+use sea_orm_migration::prelude::*;
+
+const ACCOUNT_SESSION_ACCOUNT_ID_INDEX_NAME: &str = "idx_account-session_account-id";
+const REFRESH_TOKEN_TOKEN_HASH_INDEX_NAME: &str = "idx_refresh-token_token-hash";
+const REFRESH_TOKEN_SESSION_ID_INDEX_NAME: &str = "idx_refresh-token_session-id";
+
+const ACCOUNT_SESSION_ACCOUNT_ID_FK_NAME: &str = "fk_account-session_account-id";
+const REFRESH_TOKEN_SESSION_ID_FK_NAME: &str = "fk_refresh-token_session-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccountSession::Table)
+                    .col(
+                        ColumnDef::new(AccountSession::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountSession::AccountId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccountSession::UserAgent).string())
+                    .col(
+                        ColumnDef::new(AccountSession::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountSession::LastUsedDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountSession::ExpiryDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccountSession::RevokedDate).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(ACCOUNT_SESSION_ACCOUNT_ID_FK_NAME)
+                            .from(AccountSession::Table, AccountSession::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ACCOUNT_SESSION_ACCOUNT_ID_INDEX_NAME)
+                    .table(AccountSession::Table)
+                    .col(AccountSession::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RefreshToken::Table)
+                    .col(
+                        ColumnDef::new(RefreshToken::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(RefreshToken::SessionId).integer().not_null())
+                    .col(ColumnDef::new(RefreshToken::TokenHash).string().not_null())
+                    .col(
+                        ColumnDef::new(RefreshToken::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RefreshToken::ExpiryDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RefreshToken::UsedDate).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(REFRESH_TOKEN_SESSION_ID_FK_NAME)
+                            .from(RefreshToken::Table, RefreshToken::SessionId)
+                            .to(AccountSession::Table, AccountSession::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(REFRESH_TOKEN_TOKEN_HASH_INDEX_NAME)
+                    .table(RefreshToken::Table)
+                    .col(RefreshToken::TokenHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(REFRESH_TOKEN_SESSION_ID_INDEX_NAME)
+                    .table(RefreshToken::Table)
+                    .col(RefreshToken::SessionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RefreshToken::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AccountSession::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum AccountSession {
+    Table,
+    Id,
+    AccountId,
+    UserAgent,
+    CreationDate,
+    LastUsedDate,
+    ExpiryDate,
+    RevokedDate,
+}
+
+#[derive(Iden)]
+enum RefreshToken {
+    Table,
+    Id,
+    SessionId,
+    TokenHash,
+    CreationDate,
+    ExpiryDate,
+    UsedDate,
+}
@@ -0,0 +1,221 @@
+use sea_orm_migration::prelude::*;
+
+const ROLE_NAME_INDEX_NAME: &str = "idx_role_name";
+const ROLE_PERMISSION_ROLE_ID_INDEX_NAME: &str = "idx_role-permission_role-id";
+const ACCOUNT_ROLE_ACCOUNT_ID_INDEX_NAME: &str = "idx_account-role_account-id";
+const ACCOUNT_ROLE_ROLE_ID_INDEX_NAME: &str = "idx_account-role_role-id";
+
+const ROLE_PERMISSION_ROLE_ID_FK_NAME: &str = "fk_role-permission_role-id";
+const ACCOUNT_ROLE_ACCOUNT_ID_FK_NAME: &str = "fk_account-role_account-id";
+const ACCOUNT_ROLE_ROLE_ID_FK_NAME: &str = "fk_account-role_role-id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Role::Table)
+                    .col(
+                        ColumnDef::new(Role::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Role::Name).string().not_null())
+                    .col(ColumnDef::new(Role::Description).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ROLE_NAME_INDEX_NAME)
+                    .table(Role::Table)
+                    .col(Role::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RolePermission::Table)
+                    .col(
+                        ColumnDef::new(RolePermission::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(RolePermission::RoleId).integer().not_null())
+                    .col(
+                        ColumnDef::new(RolePermission::Permission)
+                            .string()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(ROLE_PERMISSION_ROLE_ID_FK_NAME)
+                            .from(RolePermission::Table, RolePermission::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ROLE_PERMISSION_ROLE_ID_INDEX_NAME)
+                    .table(RolePermission::Table)
+                    .col(RolePermission::RoleId)
+                    .col(RolePermission::Permission)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccountRole::Table)
+                    .col(
+                        ColumnDef::new(AccountRole::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(AccountRole::AccountId).integer().not_null())
+                    .col(ColumnDef::new(AccountRole::RoleId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(ACCOUNT_ROLE_ACCOUNT_ID_FK_NAME)
+                            .from(AccountRole::Table, AccountRole::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(ACCOUNT_ROLE_ROLE_ID_FK_NAME)
+                            .from(AccountRole::Table, AccountRole::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ACCOUNT_ROLE_ACCOUNT_ID_INDEX_NAME)
+                    .table(AccountRole::Table)
+                    .col(AccountRole::AccountId)
+                    .col(AccountRole::RoleId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(ACCOUNT_ROLE_ROLE_ID_INDEX_NAME)
+                    .table(AccountRole::Table)
+                    .col(AccountRole::RoleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"INSERT INTO "role" ("name", "description") VALUES
+            ('admin', 'Full access to the store'),
+            ('catalog_editor', 'Manages products, categories, media and stock'),
+            ('order_manager', 'Handles orders and the stock they draw on'),
+            ('support', 'Helps customers with their orders and accounts')"#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"INSERT INTO "role_permission" ("role_id", "permission")
+            SELECT "role"."id", "grant"."permission" FROM "role"
+            JOIN (VALUES
+                ('admin', 'catalog:manage'),
+                ('admin', 'inventory:manage'),
+                ('admin', 'orders:view'),
+                ('admin', 'accounts:manage'),
+                ('admin', 'roles:manage'),
+                ('catalog_editor', 'catalog:manage'),
+                ('catalog_editor', 'inventory:manage'),
+                ('order_manager', 'orders:view'),
+                ('order_manager', 'inventory:manage'),
+                ('support', 'orders:view'),
+                ('support', 'accounts:manage')
+            ) AS "grant" ("role", "permission") ON "grant"."role" = "role"."name""#,
+        )
+        .await?;
+
+        // Whoever was an admin by the old role string keeps full access
+        db.execute_unprepared(
+            r#"INSERT INTO "account_role" ("account_id", "role_id")
+            SELECT "account"."id", "role"."id" FROM "account", "role"
+            WHERE "account"."role" = 'admin' AND "role"."name" = 'admin'"#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccountRole::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RolePermission::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Role::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Role {
+    Table,
+    Id,
+    Name,
+    Description,
+}
+
+#[derive(Iden)]
+enum RolePermission {
+    Table,
+    Id,
+    RoleId,
+    Permission,
+}
+
+#[derive(Iden)]
+enum AccountRole {
+    Table,
+    Id,
+    AccountId,
+    RoleId,
+}
@@ -0,0 +1,124 @@
+use sea_orm_migration::prelude::*;
+
+const LOGIN_THROTTLE_KIND_KEY_INDEX_NAME: &str = "idx_login-throttle_kind-key";
+const LOGIN_EVENT_CREATION_DATE_INDEX_NAME: &str = "idx_login-event_creation-date";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginThrottle::Table)
+                    .col(
+                        ColumnDef::new(LoginThrottle::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(LoginThrottle::Kind).string().not_null())
+                    .col(ColumnDef::new(LoginThrottle::Key).string().not_null())
+                    .col(
+                        ColumnDef::new(LoginThrottle::FailureCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(LoginThrottle::LastFailureDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LoginThrottle::LockedUntil).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(LOGIN_THROTTLE_KIND_KEY_INDEX_NAME)
+                    .table(LoginThrottle::Table)
+                    .col(LoginThrottle::Kind)
+                    .col(LoginThrottle::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Kept apart from account so the audit trail outlives deleted accounts
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginEvent::Table)
+                    .col(
+                        ColumnDef::new(LoginEvent::Id)
+                            .integer()
+                            .primary_key()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(LoginEvent::Kind).string().not_null())
+                    .col(ColumnDef::new(LoginEvent::Email).string())
+                    .col(ColumnDef::new(LoginEvent::IpAddress).string())
+                    .col(ColumnDef::new(LoginEvent::AccountId).integer())
+                    .col(ColumnDef::new(LoginEvent::ActorId).integer())
+                    .col(
+                        ColumnDef::new(LoginEvent::CreationDate)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(LOGIN_EVENT_CREATION_DATE_INDEX_NAME)
+                    .table(LoginEvent::Table)
+                    .col(LoginEvent::CreationDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginEvent::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LoginThrottle::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum LoginThrottle {
+    Table,
+    Id,
+    Kind,
+    Key,
+    FailureCount,
+    LastFailureDate,
+    LockedUntil,
+}
+
+#[derive(Iden)]
+enum LoginEvent {
+    Table,
+    Id,
+    Kind,
+    Email,
+    IpAddress,
+    AccountId,
+    ActorId,
+    CreationDate,
+}